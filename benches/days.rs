@@ -0,0 +1,96 @@
+//! Criterion benchmarks for every implemented day, with `parse`, `part1`, and `part2` measured
+//! separately on each of the day's examples, and on `input/dN` when it's there.
+//!
+//! ```text
+//! cargo bench                                 # every day
+//! cargo bench -- d7/                          # one day
+//! cargo bench -- --save-baseline main         # then on another branch:
+//! cargo bench -- --baseline main
+//! ```
+//!
+//! Reports are written to `target/criterion/report/index.html`.
+
+use std::{fs, hint::black_box, path::PathBuf};
+
+use aoc2024::{answer::Answer, solution::Day};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+/// The day's examples in `examples/` and its input, if it has one.  The examples are `dN`,
+/// `dN_2` and so on, and ones named like `dN-extra`.
+fn inputs(day: u8) -> Vec<(String, String)> {
+    let name = format!("d{day}");
+    let mut paths: Vec<PathBuf> = fs::read_dir("examples")
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let file = path.file_name()?.to_str()?;
+            let rest = file.strip_prefix(&name)?;
+            (rest.is_empty() || rest.starts_with(['_', '-'])).then_some(path)
+        })
+        .collect();
+    paths.sort();
+    paths.push(PathBuf::from(format!("input/{name}")));
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let input = fs::read_to_string(&path).ok().filter(|i| !i.is_empty())?;
+            Some((path.to_string_lossy().into_owned(), input))
+        })
+        .collect()
+}
+
+/// Benchmark a day's parse and parts on each of its inputs.  Parts that are still incomplete
+/// are skipped, and so are parts that fail on an input, like on an example that's only for the
+/// other part.  Inputs with no parts left to run, as for days that aren't implemented yet,
+/// aren't benchmarked at all.
+fn bench_day(c: &mut Criterion, day: &dyn Day) {
+    let inputs = inputs(day.day());
+    if inputs.is_empty() {
+        return;
+    }
+
+    let mut group = c.benchmark_group(format!("d{}", day.day()));
+    for (path, input) in &inputs {
+        let Ok(model) = day.parse_model(input.clone()) else {
+            continue;
+        };
+        let solved: Vec<u8> = [1, 2]
+            .into_iter()
+            .filter(|&part| !matches!(model.copy().solve(part), Ok(Answer::Incomplete) | Err(_)))
+            .collect();
+        if solved.is_empty() {
+            continue;
+        }
+
+        group.bench_with_input(BenchmarkId::new("parse", path), input, |b, input| {
+            b.iter_batched(
+                || input.clone(),
+                |input| day.parse_model(input),
+                BatchSize::SmallInput,
+            )
+        });
+        for part in solved {
+            let id = BenchmarkId::new(format!("part{part}"), path);
+            group.bench_with_input(id, &model, |b, model| {
+                b.iter_batched(
+                    || model.copy(),
+                    |model| black_box(model.solve(part)),
+                    BatchSize::SmallInput,
+                )
+            });
+        }
+    }
+    group.finish();
+}
+
+/// Benchmark every registered day.  Days that aren't implemented yet are skipped by `bench_day`.
+fn bench_all(c: &mut Criterion) {
+    for &day in aoc2024::DAYS {
+        bench_day(c, day);
+    }
+}
+
+criterion_group!(benches, bench_all);
+criterion_main!(benches);
@@ -0,0 +1,85 @@
+//! Generates a regression test for every answer stored in `answers/`, like `d7p1_example_test`
+//! for `examples/d7` or `d7p2_input_test` for `input/d7`.  They're included by
+//! `src/regression.rs`.
+//!
+//! Inputs aren't committed, so a test whose input file is missing is generated as ignored,
+//! with the reason.
+
+use std::{collections::BTreeMap, env, fmt::Write, fs, path::Path};
+
+fn main() {
+    // a missing directory always counts as changed, which would rebuild every time, so only
+    // existing ones are watched.  The first input/ added is picked up once answers/ changes.
+    for dir in ["answers", "examples", "input"] {
+        if Path::new(dir).is_dir() {
+            println!("cargo:rerun-if-changed={dir}");
+        }
+    }
+
+    let mut tests = String::new();
+    let mut days: Vec<(u8, String)> = fs::read_dir("answers")
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            let day = name
+                .strip_prefix('d')?
+                .strip_suffix(".json")?
+                .parse()
+                .ok()?;
+            Some((day, name))
+        })
+        .collect();
+    days.sort();
+
+    for (day, name) in days {
+        let path = Path::new("answers").join(&name);
+        let json = fs::read_to_string(&path).unwrap();
+        let answers: BTreeMap<String, BTreeMap<String, String>> = serde_json::from_str(&json)
+            .unwrap_or_else(|e| panic!("invalid answers file {}: {e}", path.display()));
+
+        for (input, parts) in &answers {
+            for (part, expected) in parts {
+                let Some(part) = part.strip_prefix("part").and_then(|p| p.parse::<u8>().ok())
+                else {
+                    panic!("unknown part {part} for {input} in {}", path.display());
+                };
+                let name = format!("d{day}p{part}_{}_test", test_label(day, input));
+                if !Path::new(input).exists() {
+                    writeln!(tests, "#[ignore = \"{input} is missing\"]").unwrap();
+                }
+                writeln!(
+                    tests,
+                    "#[test]\nfn {name}() -> anyhow::Result<()> {{\n    \
+                     check({day}, {part}, {input:?}, {expected:?})\n}}\n"
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("regression_tests.rs");
+    fs::write(out, tests).unwrap();
+}
+
+/// The part of a test's name that says which input it runs: `example` for `examples/d7`,
+/// `example_2` for `examples/d7_2`, `example_larger` for `examples/d7-larger`, `input` for
+/// `input/d7`, and the whole path for anything else.
+fn test_label(day: u8, input: &str) -> String {
+    let day_file = format!("d{day}");
+    let label = if let Some(rest) = input
+        .strip_prefix("examples/")
+        .and_then(|name| name.strip_prefix(&day_file))
+    {
+        format!("example{rest}")
+    } else if input == format!("input/{day_file}") {
+        "input".to_string()
+    } else {
+        input.to_string()
+    };
+
+    label
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
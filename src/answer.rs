@@ -0,0 +1,146 @@
+//! The answer to a part, shared by every day so the runner can check, print, and serialize
+//! answers the same way no matter what type a day computes them as.
+
+use std::fmt::Display;
+
+use serde::{Serialize, Serializer};
+
+/// The answer to one part of a puzzle.  Most days answer with a number, so anything numeric
+/// converts with `.into()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    /// for answers that don't fit in an `i64`
+    BigInt(i128),
+    Str(String),
+    /// multi-line text, like letters drawn on a grid
+    Grid(String),
+    /// the part hasn't been solved yet
+    Incomplete,
+}
+
+impl Answer {
+    /// Multi-line text, like letters drawn on a grid.
+    pub fn grid(text: impl Into<String>) -> Self {
+        Answer::Grid(text.into())
+    }
+
+    /// Whether this is the answer stored as `expected`.  Numbers compare by value, so `042`
+    /// matches `42`, and grids ignore trailing whitespace and blank lines around them.
+    pub fn matches(&self, expected: &str) -> bool {
+        match self {
+            Answer::Int(n) => expected.trim().parse::<i128>() == Ok(*n as i128),
+            Answer::BigInt(n) => expected.trim().parse::<i128>() == Ok(*n),
+            Answer::Str(s) => s.trim() == expected.trim(),
+            Answer::Grid(g) => grid_lines(g).eq(grid_lines(expected)),
+            Answer::Incomplete => false,
+        }
+    }
+}
+
+/// A grid's lines without trailing whitespace or blank lines before and after.
+fn grid_lines(grid: &str) -> impl Iterator<Item = &str> {
+    grid.trim_matches('\n').lines().map(str::trim_end)
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{n}"),
+            Answer::BigInt(n) => write!(f, "{n}"),
+            Answer::Str(s) | Answer::Grid(s) => write!(f, "{s}"),
+            Answer::Incomplete => write!(f, "incomplete"),
+        }
+    }
+}
+
+/// Integers are JSON numbers, except big integers which are strings since most JSON readers lose
+/// precision past 2^53.  An incomplete answer is null.
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Answer::Int(n) => serializer.serialize_i64(*n),
+            Answer::BigInt(n) => serializer.collect_str(n),
+            Answer::Str(s) | Answer::Grid(s) => serializer.serialize_str(s),
+            Answer::Incomplete => serializer.serialize_none(),
+        }
+    }
+}
+
+/// Lets tests compare against an answer as written in the puzzle.
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        self.matches(other)
+    }
+}
+
+macro_rules! from_small_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                Answer::Int(n.into())
+            }
+        })*
+    };
+}
+
+macro_rules! from_big_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                match i64::try_from(n) {
+                    Ok(n) => Answer::Int(n),
+                    Err(_) => Answer::BigInt(n.try_into().expect("answer too big for an i128")),
+                }
+            }
+        })*
+    };
+}
+
+from_small_int!(u8, u16, u32, i8, i16, i32, i64);
+from_big_int!(u64, usize, i128, u128, isize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Str(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Str(s.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers_fit_the_smallest_variant() {
+        assert_eq!(Answer::from(3749u32), Answer::Int(3749));
+        assert_eq!(Answer::from(u64::MAX), Answer::BigInt(u64::MAX as i128));
+        assert_eq!(Answer::from(-5i128), Answer::Int(-5));
+    }
+
+    #[test]
+    fn matches_stored_answers() {
+        assert!(Answer::Int(42).matches("42"));
+        assert!(Answer::Int(42).matches("042 "));
+        assert!(!Answer::Int(42).matches("forty-two"));
+        assert!(Answer::BigInt(1 << 70).matches("1180591620717411303424"));
+        assert!(Answer::from("abc").matches("abc"));
+        assert!(Answer::grid("#..\n.#.  \n").matches("\n#..\n.#."));
+        assert!(!Answer::grid("#..\n.#.").matches("#..\n..#"));
+        assert!(!Answer::Incomplete.matches("incomplete"));
+    }
+
+    #[test]
+    fn serializes_to_json() {
+        let json = |a: Answer| serde_json::to_string(&a).unwrap();
+        assert_eq!(json(Answer::Int(7)), "7");
+        assert_eq!(json(Answer::BigInt(1 << 70)), "\"1180591620717411303424\"");
+        assert_eq!(json(Answer::grid("#.\n.#")), "\"#.\\n.#\"");
+        assert_eq!(json(Answer::Incomplete), "null");
+    }
+}
@@ -0,0 +1,175 @@
+//! Known-correct answers, stored per day in `answers/dN.json` and keyed by input file.
+//!
+//! ```json
+//! {
+//!   "examples/d3": { "part1": "161" },
+//!   "examples/d3_2": { "part2": "48" }
+//! }
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::ErrorKind,
+    path::{Component, Path},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::answer::Answer;
+
+/// The directory the runner loads answers from, relative to the working directory.
+pub const ANSWERS_DIR: &str = "answers";
+
+/// The expected answers for one input file.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Expected {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part1: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part2: Option<String>,
+}
+
+impl Expected {
+    pub fn part(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part1.as_deref(),
+            2 => self.part2.as_deref(),
+            _ => None,
+        }
+    }
+
+    fn part_mut(&mut self, part: u8) -> Option<&mut Option<String>> {
+        match part {
+            1 => Some(&mut self.part1),
+            2 => Some(&mut self.part2),
+            _ => None,
+        }
+    }
+}
+
+/// The outcome of comparing an answer against the stored one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    Correct,
+    Wrong {
+        expected: String,
+    },
+    /// no answer is stored for this input and part
+    Unknown,
+}
+
+/// Every stored answer for one day.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Answers {
+    inputs: BTreeMap<String, Expected>,
+}
+
+impl Answers {
+    /// Load a day's answers from `dir`.  A missing file just means no answers are known yet.
+    pub fn load(dir: &Path, day: u8) -> Result<Self> {
+        let path = dir.join(format!("d{day}.json"));
+        match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .with_context(|| format!("invalid answers file {}", path.display())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(e).with_context(|| format!("couldn't read {}", path.display())),
+        }
+    }
+
+    /// Save the day's answers to `dir`, in the same layout they're loaded from.
+    pub fn save(&self, dir: &Path, day: u8) -> Result<()> {
+        let path = dir.join(format!("d{day}.json"));
+        let json = serde_json::to_string_pretty(self).expect("answers are always serializable");
+        fs::create_dir_all(dir).with_context(|| format!("couldn't create {}", dir.display()))?;
+        fs::write(&path, json + "\n").with_context(|| format!("couldn't write {}", path.display()))
+    }
+
+    /// Store the answer for an input's part, replacing any answer already stored.
+    pub fn set(&mut self, input: &Path, part: u8, answer: String) {
+        if let Some(expected) = self
+            .inputs
+            .entry(input_key(input))
+            .or_default()
+            .part_mut(part)
+        {
+            *expected = Some(answer);
+        }
+    }
+
+    pub fn expected(&self, input: &Path, part: u8) -> Option<&str> {
+        self.inputs.get(&input_key(input))?.part(part)
+    }
+
+    pub fn check(&self, input: &Path, part: u8, answer: &Answer) -> Check {
+        match self.expected(input, part) {
+            Some(expected) if answer.matches(expected) => Check::Correct,
+            Some(expected) => Check::Wrong {
+                expected: expected.to_string(),
+            },
+            None => Check::Unknown,
+        }
+    }
+}
+
+/// The key an input file's answers are stored under: its path with any `./` segments removed
+/// and `/` separators, so `./examples/d3_2` and `examples/d3_2` are the same input.
+pub fn input_key(path: &Path) -> String {
+    path.components()
+        .filter(|c| *c != Component::CurDir)
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_key_normalizes_paths() {
+        assert_eq!(input_key(Path::new("./examples/d3_2")), "examples/d3_2");
+        assert_eq!(input_key(Path::new("input/d7")), "input/d7");
+    }
+
+    #[test]
+    fn check_against_stored_answers() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(ANSWERS_DIR);
+        let answers = Answers::load(&dir, 3).unwrap();
+        let example = Path::new("./examples/d3_2");
+
+        assert_eq!(answers.check(example, 2, &48.into()), Check::Correct);
+        assert_eq!(
+            answers.check(example, 2, &161.into()),
+            Check::Wrong {
+                expected: "48".to_string()
+            }
+        );
+        assert_eq!(answers.check(example, 1, &161.into()), Check::Unknown);
+    }
+
+    #[test]
+    fn set_and_save_answers() {
+        let dir = std::env::temp_dir().join(format!("aoc2024-answers-{}", std::process::id()));
+        let mut answers = Answers::default();
+        answers.set(Path::new("./input/d3"), 2, "1234".to_string());
+        answers.set(Path::new("examples/d3"), 1, "161".to_string());
+        answers.save(&dir, 3).unwrap();
+
+        let saved = fs::read_to_string(dir.join("d3.json")).unwrap();
+        assert_eq!(
+            saved,
+            "{\n  \"examples/d3\": {\n    \"part1\": \"161\"\n  },\n  \"input/d3\": {\n    \"part2\": \"1234\"\n  }\n}\n"
+        );
+        assert_eq!(Answers::load(&dir, 3).unwrap(), answers);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn missing_answers_file_is_empty() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(ANSWERS_DIR);
+        assert_eq!(Answers::load(&dir, 25).unwrap(), Answers::default());
+    }
+}
@@ -1,51 +1,622 @@
+use std::{env, ffi::OsString, ops::RangeInclusive, str::FromStr, time::Duration};
+
+use tracing_subscriber::filter::LevelFilter;
+
 pub const HELP: &str = "\
-Usage: aoc2024 -d <day> [-p <part>] [-e] [-i <input>]
+Usage: aoc2024 -d <days> [-p <parts>] [-e [N]] [-i <input>]... [-f <format>] [-j <jobs>] [-t <timeout>] [--bench [N]] [--watch] [-v|-vv|-q]
+       aoc2024 serve [--host <host>] [--port <port>]
+       aoc2024 scaffold <day> [--year <year>] [--force]
+       aoc2024 fetch <days> [--example] [--year <year>] [--force] [--base-url <url>]
+       aoc2024 submit <day> <part> [<answer>] [--year <year>] [--base-url <url>]
 
 The CLI arguments allowed.
 
+Commands:
+  serve             start a local HTTP server for solving inputs pasted into a browser
+  scaffold          create a new day's source and example files from templates/d.rs, and
+                    register the day in src/lib.rs
+  fetch             download the days' inputs to input/ (needs AOC_SESSION)
+  submit            send an answer, or the part's answer for input/dN when none is given, and
+                    save it to answers/ if it's right (needs AOC_SESSION)
+
 Options:
-  -d, --day         specifies the day
-  -p, --part        specifies the part
-  -e, --example     use the day's example input from examples/
-  -i, --input       specify an alternate input file
+  -d, --day         specifies the days: 3, 3-7, 1,4,9-12, or all
+  -p, --part        specifies the parts: 1, 2, or 1,2 (default)
+  -e, --example [N] run every example input in examples/, or just example N
+  -i, --input       specify an alternate input file, or - for stdin; repeat to run several
+  -f, --format      output format: pretty (default), json, or tsv
+  -j, --jobs        run days concurrently on this many threads (0 for one per core)
+  -t, --timeout     give up on any part still running after this long, like 30s or 500ms
+      --bench [N]   benchmark the selected parts over N runs (default 100)
+      --baseline    compare benchmark results against a saved baseline file
+      --save-baseline
+                    save benchmark results to a baseline file
+  -w, --watch       re-run whenever the days' source, examples, or input change
+      --tick-ms     how long each frame of a day's visualization is shown, in milliseconds
+                    (default 20, needs the visualize feature)
+      --record      also record visualizations to an asciinema .cast file, even when stdout
+                    isn't a terminal (needs the visualize feature)
+  -v, --verbose     log debug diagnostics to stderr, -vv for trace too; works with every command
+  -q, --quiet       don't log anything, not even warnings
   -h, --help        display usage information
+
+RUST_LOG overrides -v and -q, and each day logs under its own target, so
+RUST_LOG=aoc2024::d6=trace traces just day 6.
+
+Serve options:
+      --host        the address to listen on (default 127.0.0.1)
+      --port        the port to listen on, 0 for any free port (default 2024)
+
+Scaffold options:
+      --year        the puzzle year for the links in the new source (default $AOC_YEAR or 2024)
+      --force       overwrite the day's source file if it already exists
+
+Fetch options:
+      --example     also save the first example on each day's puzzle page to examples/
+      --year        the puzzle year to download (default $AOC_YEAR or 2024)
+      --force       download again even when the files already exist
+      --base-url    the site to download from (default $AOC_BASE_URL or https://adventofcode.com)
+
+Submit options:
+      --year        the puzzle year to answer (default $AOC_YEAR or 2024)
+      --base-url    the site to answer to (default $AOC_BASE_URL or https://adventofcode.com)
 ";
 
 pub const INPUT_CONFLICT: &str = "\
 Error: -i/--input and -e/--example can't be used together.
 ";
 
-/// The CLI arguments allowed.
-pub struct Args {
-    /// specifies the day (255 runs all parts)
+/// How each part's result is printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// colored text for humans
+    #[default]
+    Pretty,
+    /// one JSON object per line
+    Json,
+    /// tab separated values, with a header line
+    Tsv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pretty" => Ok(Format::Pretty),
+            "json" => Ok(Format::Json),
+            "tsv" => Ok(Format::Tsv),
+            _ => Err(format!("unknown format: {s}")),
+        }
+    }
+}
+
+/// Which of a day's example inputs to run.  The first example is `examples/dN`, and any more are
+/// `examples/dN_2`, `examples/dN_3`, and so on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Example {
+    /// every example for the day, including ones named like `examples/dN-extra`
+    All,
+    /// just the numbered example
+    Variant(u8),
+}
+
+/// Take `-e`/`--example` and its optional example number out of the raw arguments.  pico-args
+/// can't express an optional value, it would take a following option like `-f` as the number.
+fn take_example(raw: &mut Vec<OsString>) -> Result<Option<Example>, pico_args::Error> {
+    let Some(idx) = raw.iter().position(|arg| arg == "-e" || arg == "--example") else {
+        return Ok(None);
+    };
+    raw.remove(idx);
+
+    let number = raw.get(idx).and_then(|arg| arg.to_str()).and_then(|arg| {
+        arg.starts_with(|c: char| c.is_ascii_digit())
+            .then(|| arg.to_string())
+    });
+    let Some(number) = number else {
+        return Ok(Some(Example::All));
+    };
+    raw.remove(idx);
+
+    match number.parse::<u8>() {
+        Ok(n) if n > 0 => Ok(Some(Example::Variant(n))),
+        _ => Err(pico_args::Error::Utf8ArgumentParsingFailed {
+            value: number,
+            cause: "the example number must be from 1 to 255".to_string(),
+        }),
+    }
+}
+
+/// Options of any command that take a value, which is left alone even if it looks like `-v`.
+const VALUE_OPTIONS: &[&str] = &[
+    "-d",
+    "--day",
+    "-p",
+    "--part",
+    "-i",
+    "--input",
+    "-f",
+    "--format",
+    "-j",
+    "--jobs",
+    "-t",
+    "--timeout",
+    "--baseline",
+    "--save-baseline",
+    "--tick-ms",
+    "--record",
+    "--host",
+    "--port",
+    "--year",
+    "--base-url",
+];
+
+/// Take every `-v`, `-vv`, `--verbose`, `-q`, and `--quiet` out of the raw arguments, wherever
+/// they are, and work out how much to log.  pico-args can't count a repeated flag like `-vv`.
+fn take_verbosity(raw: &mut Vec<OsString>) -> LevelFilter {
+    let mut verbose = 0;
+    let mut quiet = false;
+    let mut is_value = false;
+    raw.retain(|arg| {
+        if std::mem::replace(&mut is_value, false) {
+            return true;
+        }
+        match arg.to_str() {
+            Some("-q" | "--quiet") => quiet = true,
+            Some("--verbose") => verbose += 1,
+            Some(flag)
+                if flag.len() > 1
+                    && flag.starts_with('-')
+                    && flag[1..].bytes().all(|b| b == b'v') =>
+            {
+                verbose += flag.len() - 1
+            }
+            Some(option) => {
+                is_value = VALUE_OPTIONS.contains(&option);
+                return true;
+            }
+            None => return true,
+        }
+        false
+    });
+
+    match (quiet, verbose) {
+        (true, _) => LevelFilter::OFF,
+        (false, 0) => LevelFilter::WARN,
+        (false, 1) => LevelFilter::DEBUG,
+        (false, _) => LevelFilter::TRACE,
+    }
+}
+
+/// Days or parts chosen on the command line, as a single number (`3`), a range (`3-7`), a comma
+/// separated list of either (`1,4,9-12`), or `all`.  Sorted and de-duplicated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection(pub Vec<u8>);
+
+impl Selection {
+    fn parse(s: &str, valid: RangeInclusive<u8>) -> Result<Self, String> {
+        if s == "all" {
+            return Ok(Selection(valid.collect()));
+        }
+
+        let parse_num = |n: &str| -> Result<u8, String> {
+            n.trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|n| valid.contains(n))
+                .ok_or_else(|| {
+                    format!(
+                        "{n} must be a number from {} to {}",
+                        valid.start(),
+                        valid.end()
+                    )
+                })
+        };
+
+        let mut selected = vec![];
+        for item in s.split(',') {
+            if let Some((start, end)) = item.split_once('-') {
+                let (start, end) = (parse_num(start)?, parse_num(end)?);
+                if start > end {
+                    return Err(format!("range {item} is backwards"));
+                }
+                selected.extend(start..=end);
+            } else {
+                selected.push(parse_num(item)?);
+            }
+        }
+
+        selected.sort_unstable();
+        selected.dedup();
+        Ok(Selection(selected))
+    }
+
+    pub fn days(s: &str) -> Result<Self, String> {
+        Selection::parse(s, 1..=25)
+    }
+
+    pub fn parts(s: &str) -> Result<Self, String> {
+        Selection::parse(s, 1..=2)
+    }
+
+    /// A single day, for commands that only work on one.
+    pub fn one_day(s: &str) -> Result<u8, String> {
+        match Selection::days(s)?.0[..] {
+            [day] => Ok(day),
+            _ => Err(format!("expected a single day, not {s}")),
+        }
+    }
+
+    /// A single part, for commands that only work on one.
+    pub fn one_part(s: &str) -> Result<u8, String> {
+        match Selection::parts(s)?.0[..] {
+            [part] => Ok(part),
+            _ => Err(format!("expected a single part, not {s}")),
+        }
+    }
+}
+
+/// Parse a duration like `500ms`, `30s`, or `2m`.  A bare number is in seconds.
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let num: f64 = num.parse().map_err(|_| format!("invalid duration: {s}"))?;
+
+    let secs = match unit {
+        "ms" => num / 1000.0,
+        "" | "s" => num,
+        "m" => num * 60.0,
+        _ => return Err(format!("unknown duration unit in {s}, use ms, s, or m")),
+    };
+
+    Duration::try_from_secs_f64(secs).map_err(|_| format!("invalid duration: {s}"))
+}
+
+/// What the binary was asked to do.
+pub enum Command {
+    /// run the selected days, the default when there's no subcommand
+    Run(Args),
+    Serve(ServeArgs),
+    Scaffold(ScaffoldArgs),
+    Fetch(FetchArgs),
+    Submit(SubmitArgs),
+}
+
+/// The arguments for `aoc2024 serve`.
+pub struct ServeArgs {
+    pub host: String,
+    pub port: u16,
+}
+
+/// The arguments for `aoc2024 scaffold`.
+pub struct ScaffoldArgs {
     pub day: u8,
-    /// specifies the part
-    pub part: Option<u8>,
-    /// use the day's example input from examples/
+    pub year: u16,
+    /// overwrite the day's source file if it already exists
+    pub force: bool,
+}
+
+/// The arguments for `aoc2024 fetch`.
+pub struct FetchArgs {
+    pub days: Selection,
+    pub year: u16,
+    /// also save each day's first example
     pub example: bool,
-    /// specify an alternate input file
-    pub input: Option<String>,
+    /// download again even when the files already exist
+    pub force: bool,
+    pub base_url: String,
 }
 
-pub fn parse_args() -> Result<Args, pico_args::Error> {
-    let mut pargs = pico_args::Arguments::from_env();
+/// The arguments for `aoc2024 submit`.
+pub struct SubmitArgs {
+    pub day: u8,
+    pub part: u8,
+    /// the answer to send, or None to solve the part for the day's input
+    pub answer: Option<String>,
+    pub year: u16,
+    pub base_url: String,
+}
+
+/// The CLI arguments allowed.
+pub struct Args {
+    /// specifies the days
+    pub days: Selection,
+    /// specifies the parts
+    pub parts: Selection,
+    /// run the day's example inputs from examples/
+    pub example: Option<Example>,
+    /// alternate input files to run, `-` meaning stdin
+    pub input: Vec<String>,
+    /// output format
+    pub format: Format,
+    /// run days concurrently on this many threads
+    pub jobs: Option<usize>,
+    /// give up on parts that run longer than this
+    pub timeout: Option<Duration>,
+    /// benchmark over this many runs instead of running once
+    pub bench: Option<usize>,
+    /// baseline file to compare benchmark results against
+    pub baseline: Option<String>,
+    /// baseline file to save benchmark results to
+    pub save_baseline: Option<String>,
+    /// re-run whenever the days' files change
+    pub watch: bool,
+    /// how long each frame of a visualization is shown
+    pub tick: Option<Duration>,
+    /// asciinema file to record visualizations to
+    pub record: Option<String>,
+}
+
+/// The puzzle year from `AOC_YEAR`, which the justfile loads from `.env`, or 2024.
+fn default_year() -> u16 {
+    env::var("AOC_YEAR")
+        .ok()
+        .and_then(|year| year.parse().ok())
+        .unwrap_or(2024)
+}
 
-    if pargs.contains(["-h", "--help"]) {
+/// The site from `AOC_BASE_URL`, or the real one.
+fn default_base_url() -> String {
+    env::var("AOC_BASE_URL").unwrap_or_else(|_| crate::site::DEFAULT_BASE_URL.to_string())
+}
+
+/// Fail on any argument that nothing took, like a mistyped option, instead of ignoring it.
+fn finish(pargs: pico_args::Arguments) -> Result<(), pico_args::Error> {
+    let unused = pargs.finish();
+    if unused.is_empty() {
+        return Ok(());
+    }
+    Err(pico_args::Error::Utf8ArgumentParsingFailed {
+        value: unused
+            .iter()
+            .map(|arg| arg.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" "),
+        cause: "unexpected argument".to_string(),
+    })
+}
+
+/// The command to run, and how much to log while running it.
+pub fn parse_args() -> Result<(Command, LevelFilter), pico_args::Error> {
+    let mut raw: Vec<OsString> = env::args_os().skip(1).collect();
+    let verbosity = take_verbosity(&mut raw);
+    Ok((parse_command(raw)?, verbosity))
+}
+
+pub fn parse_command(mut raw: Vec<OsString>) -> Result<Command, pico_args::Error> {
+    if raw.iter().any(|arg| arg == "-h" || arg == "--help") {
         print!("{HELP}");
         std::process::exit(0);
     }
 
-    let args = Args {
-        day: pargs.value_from_str(["-d", "--day"])?,
-        part: pargs.value_from_str(["-p", "--part"]).ok(),
-        example: pargs.contains(["-e", "--example"]),
-        input: pargs.opt_value_from_str(["-i", "--input"])?,
+    if raw.first().is_some_and(|arg| arg == "serve") {
+        let mut pargs = pico_args::Arguments::from_vec(raw.split_off(1));
+        let host = pargs
+            .opt_value_from_str("--host")?
+            .unwrap_or_else(|| "127.0.0.1".to_string());
+        let port = pargs.opt_value_from_str("--port")?.unwrap_or(2024);
+        finish(pargs)?;
+        return Ok(Command::Serve(ServeArgs { host, port }));
+    }
+
+    if raw.first().is_some_and(|arg| arg == "scaffold") {
+        let mut pargs = pico_args::Arguments::from_vec(raw.split_off(1));
+        let year = pargs
+            .opt_value_from_str("--year")?
+            .unwrap_or_else(default_year);
+        let force = pargs.contains("--force");
+        let day = pargs.free_from_fn(Selection::one_day)?;
+        finish(pargs)?;
+        return Ok(Command::Scaffold(ScaffoldArgs { day, year, force }));
+    }
+
+    if raw.first().is_some_and(|arg| arg == "fetch") {
+        let mut pargs = pico_args::Arguments::from_vec(raw.split_off(1));
+        let year = pargs
+            .opt_value_from_str("--year")?
+            .unwrap_or_else(default_year);
+        let example = pargs.contains("--example");
+        let force = pargs.contains("--force");
+        let base_url = pargs
+            .opt_value_from_str("--base-url")?
+            .unwrap_or_else(default_base_url);
+        let days = pargs.free_from_fn(Selection::days)?;
+        finish(pargs)?;
+        return Ok(Command::Fetch(FetchArgs {
+            days,
+            year,
+            example,
+            force,
+            base_url,
+        }));
+    }
+
+    if raw.first().is_some_and(|arg| arg == "submit") {
+        let mut pargs = pico_args::Arguments::from_vec(raw.split_off(1));
+        let year = pargs
+            .opt_value_from_str("--year")?
+            .unwrap_or_else(default_year);
+        let base_url = pargs
+            .opt_value_from_str("--base-url")?
+            .unwrap_or_else(default_base_url);
+        let day = pargs.free_from_fn(Selection::one_day)?;
+        let part = pargs.free_from_fn(Selection::one_part)?;
+        let answer = pargs.opt_free_from_str()?;
+        finish(pargs)?;
+        return Ok(Command::Submit(SubmitArgs {
+            day,
+            part,
+            answer,
+            year,
+            base_url,
+        }));
+    }
+
+    let example = take_example(&mut raw)?;
+    let mut pargs = pico_args::Arguments::from_vec(raw);
+
+    let mut args = Args {
+        days: pargs.value_from_fn(["-d", "--day"], Selection::days)?,
+        parts: pargs
+            .opt_value_from_fn(["-p", "--part"], Selection::parts)?
+            .unwrap_or_else(|| Selection(vec![1, 2])),
+        example,
+        input: pargs.values_from_str(["-i", "--input"])?,
+        format: pargs
+            .opt_value_from_str(["-f", "--format"])?
+            .unwrap_or_default(),
+        jobs: pargs.opt_value_from_str(["-j", "--jobs"])?,
+        timeout: pargs.opt_value_from_fn(["-t", "--timeout"], parse_duration)?,
+        bench: None,
+        baseline: pargs.opt_value_from_str("--baseline")?,
+        save_baseline: pargs.opt_value_from_str("--save-baseline")?,
+        watch: pargs.contains(["-w", "--watch"]),
+        tick: pargs
+            .opt_value_from_str("--tick-ms")?
+            .map(Duration::from_millis),
+        record: pargs.opt_value_from_str("--record")?,
     };
 
-    if pargs.contains(["-e", "--example"]) && pargs.contains(["-i", "--input"]) {
+    // the run count is optional, so take it from whatever free argument is left over once every
+    // other option has been parsed
+    if pargs.contains("--bench") {
+        args.bench = Some(
+            pargs
+                .opt_free_from_str()?
+                .unwrap_or(crate::bench::DEFAULT_RUNS),
+        );
+    }
+    finish(pargs)?;
+
+    if args.example.is_some() && !args.input.is_empty() {
         print!("{INPUT_CONFLICT}");
         std::process::exit(1);
     }
 
-    Ok(args)
+    Ok(Command::Run(args))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_from_str() {
+        assert_eq!("pretty".parse(), Ok(Format::Pretty));
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert_eq!("tsv".parse(), Ok(Format::Tsv));
+        assert!("yaml".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn selection_days() {
+        assert_eq!(Selection::days("3"), Ok(Selection(vec![3])));
+        assert_eq!(Selection::days("3-7"), Ok(Selection(vec![3, 4, 5, 6, 7])));
+        assert_eq!(
+            Selection::days("9-12,1,4,4"),
+            Ok(Selection(vec![1, 4, 9, 10, 11, 12]))
+        );
+        assert_eq!(Selection::days("all"), Ok(Selection((1..=25).collect())));
+        assert!(Selection::days("0").is_err());
+        assert!(Selection::days("26").is_err());
+        assert!(Selection::days("7-3").is_err());
+        assert!(Selection::days("x").is_err());
+    }
+
+    #[test]
+    fn example_with_optional_number() {
+        let take = |args: &[&str]| {
+            let mut raw: Vec<OsString> = args.iter().map(OsString::from).collect();
+            let example = take_example(&mut raw).ok()?;
+            Some((example, raw))
+        };
+
+        assert_eq!(
+            take(&["-d", "4"]),
+            Some((None, vec!["-d".into(), "4".into()]))
+        );
+        assert_eq!(
+            take(&["-d", "4", "-e"]),
+            Some((Some(Example::All), vec!["-d".into(), "4".into()]))
+        );
+        assert_eq!(
+            take(&["-e", "-f", "json"]),
+            Some((Some(Example::All), vec!["-f".into(), "json".into()]))
+        );
+        assert_eq!(
+            take(&["--example", "3", "-d", "4"]),
+            Some((Some(Example::Variant(3)), vec!["-d".into(), "4".into()]))
+        );
+        assert_eq!(take(&["-e", "0"]), None);
+    }
+
+    #[test]
+    fn verbosity_flags_anywhere() {
+        let take = |args: &[&str]| {
+            let mut raw: Vec<OsString> = args.iter().map(OsString::from).collect();
+            (take_verbosity(&mut raw), raw)
+        };
+
+        assert_eq!(
+            take(&["-d", "4"]),
+            (LevelFilter::WARN, vec!["-d".into(), "4".into()])
+        );
+        assert_eq!(
+            take(&["-v", "-d", "4"]),
+            (LevelFilter::DEBUG, vec!["-d".into(), "4".into()])
+        );
+        assert_eq!(
+            take(&["serve", "-vv"]),
+            (LevelFilter::TRACE, vec!["serve".into()])
+        );
+        assert_eq!(take(&["-v", "--verbose"]).0, LevelFilter::TRACE);
+        assert_eq!(take(&["-vv", "-q"]).0, LevelFilter::OFF);
+        assert_eq!(take(&["-"]), (LevelFilter::WARN, vec!["-".into()]));
+        assert_eq!(take(&["-vx"]).1, vec![OsString::from("-vx")]);
+        assert_eq!(take(&["é"]), (LevelFilter::WARN, vec!["é".into()]));
+        assert_eq!(take(&["xv"]), (LevelFilter::WARN, vec!["xv".into()]));
+        assert_eq!(
+            take(&["-i", "-v", "-d", "4", "-q"]),
+            (
+                LevelFilter::OFF,
+                vec!["-i".into(), "-v".into(), "-d".into(), "4".into()]
+            )
+        );
+        assert_eq!(
+            take(&["--record", "-vv"]),
+            (LevelFilter::WARN, vec!["--record".into(), "-vv".into()])
+        );
+    }
+
+    #[test]
+    fn unknown_arguments_are_rejected() {
+        let parse = |args: &[&str]| parse_command(args.iter().map(OsString::from).collect());
+
+        assert!(parse(&["-d", "4", "-t", "5s", "--bench", "10"]).is_ok());
+        assert!(parse(&["-d", "4", "--timout", "5s"]).is_err());
+        assert!(parse(&["-d", "4", "--bench", "10", "extra"]).is_err());
+        assert!(parse(&["serve", "--prot", "80"]).is_err());
+        assert!(parse(&["scaffold", "4", "5"]).is_err());
+        assert!(parse(&["fetch", "4", "--exmaple"]).is_err());
+        assert!(parse(&["submit", "4", "1", "42", "43"]).is_err());
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("1.5"), Ok(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert!(parse_duration("2h").is_err());
+        assert!(parse_duration("soon").is_err());
+    }
+
+    #[test]
+    fn selection_parts() {
+        assert_eq!(Selection::parts("1,2"), Ok(Selection(vec![1, 2])));
+        assert_eq!(Selection::parts("2"), Ok(Selection(vec![2])));
+        assert!(Selection::parts("3").is_err());
+    }
 }
@@ -0,0 +1,237 @@
+use std::{collections::BTreeMap, fs, io, path::Path, time::Duration};
+
+use aoc2024::solution::{Day, Failure};
+use serde::{Deserialize, Serialize};
+use termion::{color, style};
+
+use crate::{args::Format, output::fmt_duration};
+
+/// Number of runs used by `--bench` when no count is given.
+pub const DEFAULT_RUNS: usize = 100;
+
+/// How much slower than the baseline median a measurement must be before it's flagged.
+const REGRESSION_THRESHOLD: f64 = 0.10;
+
+/// Summary statistics for repeated timings of one step (the parse, or one part).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub runs: usize,
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub mean_ns: u64,
+    pub stddev_ns: u64,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "can't summarize zero samples");
+
+        let mut ns: Vec<f64> = samples.iter().map(|s| s.as_nanos() as f64).collect();
+        ns.sort_by(|a, b| a.total_cmp(b));
+
+        let n = ns.len() as f64;
+        let mean = ns.iter().sum::<f64>() / n;
+        let variance = ns.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n;
+        let median = if ns.len().is_multiple_of(2) {
+            (ns[ns.len() / 2 - 1] + ns[ns.len() / 2]) / 2.0
+        } else {
+            ns[ns.len() / 2]
+        };
+
+        Stats {
+            runs: ns.len(),
+            min_ns: ns[0] as u64,
+            median_ns: median as u64,
+            mean_ns: mean as u64,
+            stddev_ns: variance.sqrt() as u64,
+        }
+    }
+
+    /// The fractional change in median from the baseline, if it's slower by more than the
+    /// regression threshold.  A baseline median of zero is below the timer's resolution, so
+    /// there's nothing to compare against.
+    pub fn regression(&self, baseline: &Stats) -> Option<f64> {
+        if baseline.median_ns == 0 {
+            return None;
+        }
+        let change =
+            (self.median_ns as f64 - baseline.median_ns as f64) / baseline.median_ns as f64;
+        (change > REGRESSION_THRESHOLD).then_some(change)
+    }
+}
+
+/// One benchmarked step of a day, e.g. `d7.parse` or `d7.part1`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Measurement {
+    pub key: String,
+    pub answer: Option<String>,
+    pub stats: Stats,
+    /// how much slower than the baseline this was, when it's a regression
+    pub regression: Option<f64>,
+}
+
+/// Saved benchmark results, keyed by measurement (e.g. `d7.part1`).
+pub type Baseline = BTreeMap<String, Stats>;
+
+/// Load a baseline file, or an empty baseline if the file doesn't exist yet.
+pub fn load_baseline(path: &Path) -> io::Result<Baseline> {
+    match fs::read_to_string(path) {
+        Ok(contents) => serde_json::from_str(&contents).map_err(io::Error::other),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Baseline::new()),
+        Err(e) => Err(e),
+    }
+}
+
+pub fn save_baseline(path: &Path, baseline: &Baseline) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(baseline).map_err(io::Error::other)?;
+    fs::write(path, json + "\n")
+}
+
+/// Warm up, then run the given parts of a day `runs` times, summarizing the parse and each part.
+/// Stops at the first part that fails, since its timings wouldn't mean anything.
+pub fn bench_day(
+    solution: &dyn Day,
+    input: &str,
+    parts: &[u8],
+    runs: usize,
+    baseline: &Baseline,
+) -> Result<Vec<Measurement>, Failure> {
+    let warmup = (runs / 10).max(1);
+    for _ in 0..warmup {
+        for part in solution.run(input.to_string(), parts).parts {
+            part.answer?;
+        }
+    }
+
+    let mut parse_samples = Vec::with_capacity(runs);
+    let mut part_samples = vec![Vec::with_capacity(runs); parts.len()];
+    let mut answers = vec![String::new(); parts.len()];
+
+    for _ in 0..runs {
+        let result = solution.run(input.to_string(), parts);
+        parse_samples.push(result.parse_time);
+        for (i, part) in result.parts.into_iter().enumerate() {
+            part_samples[i].push(part.time);
+            answers[i] = part.answer?.to_string();
+        }
+    }
+
+    let day = solution.day();
+    let mut measurements = vec![(format!("d{day}.parse"), None, parse_samples)];
+    for ((part, answer), samples) in parts.iter().zip(answers).zip(part_samples) {
+        measurements.push((format!("d{day}.part{part}"), Some(answer), samples));
+    }
+
+    Ok(measurements
+        .into_iter()
+        .map(|(key, answer, samples)| {
+            let stats = Stats::from_samples(&samples);
+            let regression = baseline.get(&key).and_then(|b| stats.regression(b));
+            Measurement {
+                key,
+                answer,
+                stats,
+                regression,
+            }
+        })
+        .collect())
+}
+
+pub fn print_header(format: Format) {
+    if format == Format::Tsv {
+        println!("key\tanswer\truns\tmin_ns\tmedian_ns\tmean_ns\tstddev_ns\tregression");
+    }
+}
+
+pub fn print_measurement(format: Format, m: &Measurement) {
+    match format {
+        Format::Pretty => print_pretty(m),
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string(m).expect("measurements are always serializable")
+        ),
+        Format::Tsv => println!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            m.key,
+            m.answer.as_deref().unwrap_or(""),
+            m.stats.runs,
+            m.stats.min_ns,
+            m.stats.median_ns,
+            m.stats.mean_ns,
+            m.stats.stddev_ns,
+            m.regression.map(|r| r.to_string()).unwrap_or_default(),
+        ),
+    }
+}
+
+fn print_pretty(m: &Measurement) {
+    let ns = |n: u64| fmt_duration(Duration::from_nanos(n));
+    let regression = m
+        .regression
+        .map(|r| {
+            format!(
+                " {red}▲ {pct:.1}% slower than baseline{reset}",
+                red = color::Fg(color::Red),
+                reset = style::Reset,
+                pct = r * 100.0,
+            )
+        })
+        .unwrap_or_default();
+
+    println!(
+        "{green}🎄{reset} {blue}{key}{reset} {answer}{grey}min {min} · median {median} · mean {mean} · σ {stddev} ({runs} runs){reset}{regression}",
+        blue = color::Fg(color::Blue),
+        green = color::Fg(color::Green),
+        grey = color::Fg(color::LightBlack),
+        reset = style::Reset,
+        key = m.key,
+        answer = m.answer.as_ref().map(|a| format!("{a} ")).unwrap_or_default(),
+        min = ns(m.stats.min_ns),
+        median = ns(m.stats.median_ns),
+        mean = ns(m.stats.mean_ns),
+        stddev = ns(m.stats.stddev_ns),
+        runs = m.stats.runs,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ns(samples: &[u64]) -> Vec<Duration> {
+        samples.iter().map(|n| Duration::from_nanos(*n)).collect()
+    }
+
+    #[test]
+    fn stats_from_samples() {
+        let stats = Stats::from_samples(&ns(&[4, 2, 8, 6]));
+        assert_eq!(
+            stats,
+            Stats {
+                runs: 4,
+                min_ns: 2,
+                median_ns: 5,
+                mean_ns: 5,
+                stddev_ns: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn regression_threshold() {
+        let baseline = Stats::from_samples(&ns(&[100]));
+        assert_eq!(Stats::from_samples(&ns(&[105])).regression(&baseline), None);
+        assert_eq!(Stats::from_samples(&ns(&[90])).regression(&baseline), None);
+        assert_eq!(
+            Stats::from_samples(&ns(&[150])).regression(&baseline),
+            Some(0.5)
+        );
+    }
+
+    #[test]
+    fn no_regression_from_zero_baseline() {
+        let baseline = Stats::from_samples(&ns(&[0]));
+        assert_eq!(Stats::from_samples(&ns(&[0])).regression(&baseline), None);
+        assert_eq!(Stats::from_samples(&ns(&[50])).regression(&baseline), None);
+    }
+}
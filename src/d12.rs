@@ -1,57 +1,41 @@
 //! A solution to day 12 year 2024.
 //! https://adventofcode.com/2024/day/12
 
+use anyhow::Result;
+
+use crate::{answer::Answer, solution::Solution};
+
 type Model = u8;
-type Answer = String;
 
-pub fn parse(input: String) -> Model {
-    0
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 12;
+    const TITLE: &'static str = "Garden Groups";
+
+    type Model = Model;
+
+    fn parse(input: String) -> Result<Model> {
+        parse(input)
+    }
+
+    fn part1(model: Model) -> Result<Answer> {
+        part1(model)
+    }
+
+    fn part2(model: Model) -> Result<Answer> {
+        part2(model)
+    }
 }
 
-pub fn part1(model: Model) -> Answer {
-    "incomplete".to_string()
+pub fn parse(input: String) -> Result<Model> {
+    Ok(0)
 }
 
-pub fn part2(model: Model) -> Answer {
-    "incomplete".to_string()
+pub fn part1(model: Model) -> Result<Answer> {
+    Ok(Answer::Incomplete)
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use super::*;
-//
-//     const INPUT: &str = include_str!("../input/d12");
-//     const EXAMPLE: &str = include_str!("../examples/d12");
-//
-//     // #[test]
-//     // fn d12p1_example_test() {
-//     //     assert_eq!(
-//     //         part1(parse(EXAMPLE.to_string())),
-//     //         "put part 1 example answer here"
-//     //     );
-//     // }
-//     //
-//     // #[test]
-//     // fn d12p1_input_test() {
-//     //     assert_eq!(
-//     //         part1(parse(INPUT.to_string())),
-//     //         "put part 1 final answer here"
-//     //     );
-//     // }
-//     //
-//     // #[test]
-//     // fn d12p2_example_test() {
-//     //     assert_eq!(
-//     //         part2(parse(EXAMPLE.to_string())),
-//     //         "put part 2 example answer here"
-//     //     );
-//     // }
-//     //
-//     // #[test]
-//     // fn d12p2_input_test() {
-//     //     assert_eq!(
-//     //         part2(parse(INPUT.to_string())),
-//     //         "put part 2 final answer here"
-//     //     );
-//     // }
-// }
+pub fn part2(model: Model) -> Result<Answer> {
+    Ok(Answer::Incomplete)
+}
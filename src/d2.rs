@@ -3,10 +3,16 @@
 
 use std::cmp::Ordering;
 
+use anyhow::{ensure, Result};
 use itertools::PeekingNext;
 
+use crate::{
+    answer::Answer,
+    input::{parse_at, parse_lines},
+    solution::Solution,
+};
+
 type Model = Vec<Report>;
-type Answer = u32;
 
 #[derive(Debug)]
 pub enum Direction {
@@ -16,30 +22,36 @@ pub enum Direction {
 
 const DELTA_THRESHOLD: u8 = 3;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Report {
     pub error_damped: bool,
     pub levels: Vec<u8>,
 }
 
-impl From<&str> for Report {
-    fn from(s: &str) -> Self {
-        let levels = s
+impl TryFrom<&str> for Report {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        let levels: Vec<u8> = s
             .split_whitespace()
-            .filter_map(|num| num.parse::<u8>().ok())
-            .collect();
+            .map(|num| parse_at(s, num))
+            .collect::<Result<_>>()?;
+        ensure!(levels.len() >= 2, "a report needs at least two levels");
 
-        Report {
+        Ok(Report {
             levels,
             error_damped: false,
-        }
+        })
     }
 }
 
 impl Report {
     pub fn is_safe_no_retry(&self, levels: &[u8]) -> bool {
-        let first = levels[0];
-        let second = levels[1];
+        // with fewer than two levels there's nothing to be unsafe, which happens in part 2 when
+        // a level is removed from a two level report
+        let [first, second, ..] = levels[..] else {
+            return true;
+        };
 
         let direction = match first.cmp(&second) {
             Ordering::Greater => Direction::Desc,
@@ -97,20 +109,33 @@ impl Report {
     }
 }
 
-pub fn parse(input: String) -> Model {
-    let mut reports: Vec<Report> = vec![];
+pub struct Solver;
 
-    // read each line and parse each report
-    let lines: Vec<&str> = input.trim().split("\n").collect();
+impl Solution for Solver {
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Red-Nosed Reports";
+
+    type Model = Model;
+
+    fn parse(input: String) -> Result<Model> {
+        parse(input)
+    }
 
-    for line in lines {
-        reports.push(Report::from(line));
+    fn part1(model: Model) -> Result<Answer> {
+        part1(model)
     }
 
-    reports
+    fn part2(model: Model) -> Result<Answer> {
+        part2(model)
+    }
+}
+
+pub fn parse(input: String) -> Result<Model> {
+    // read each line and parse each report
+    parse_lines(&input, |line| Report::try_from(line))
 }
 
-pub fn part1(model: Model) -> Answer {
+pub fn part1(model: Model) -> Result<Answer> {
     let mut total_safe: u32 = 0;
 
     model.iter().for_each(|r| {
@@ -119,10 +144,10 @@ pub fn part1(model: Model) -> Answer {
         }
     });
 
-    total_safe
+    Ok(total_safe.into())
 }
 
-pub fn part2(mut model: Model) -> Answer {
+pub fn part2(mut model: Model) -> Result<Answer> {
     let mut total_safe: u32 = 0;
 
     model.iter().for_each(|r| {
@@ -131,45 +156,19 @@ pub fn part2(mut model: Model) -> Answer {
         }
     });
 
-    total_safe
+    Ok(total_safe.into())
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use super::*;
-//
-//     const INPUT: &str = include_str!("../input/d2");
-//     const EXAMPLE: &str = include_str!("../examples/d2");
-//
-//     // #[test]
-//     // fn d2p1_example_test() {
-//     //     assert_eq!(
-//     //         part1(parse(EXAMPLE.to_string())),
-//     //         "put part 1 example answer here"
-//     //     );
-//     // }
-//     //
-//     // #[test]
-//     // fn d2p1_input_test() {
-//     //     assert_eq!(
-//     //         part1(parse(INPUT.to_string())),
-//     //         "put part 1 final answer here"
-//     //     );
-//     // }
-//     //
-//     // #[test]
-//     // fn d2p2_example_test() {
-//     //     assert_eq!(
-//     //         part2(parse(EXAMPLE.to_string())),
-//     //         "put part 2 example answer here"
-//     //     );
-//     // }
-//     //
-//     // #[test]
-//     // fn d2p2_input_test() {
-//     //     assert_eq!(
-//     //         part2(parse(INPUT.to_string())),
-//     //         "put part 2 final answer here"
-//     //     );
-//     // }
-// }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_level_reports() {
+        let model = parse("1 1\n1 9\n1 2\n".to_string()).unwrap();
+
+        assert_eq!(part1(model.clone()).unwrap(), Answer::from(1u32));
+        // removing either level leaves a single level, which is safe
+        assert_eq!(part2(model).unwrap(), Answer::from(3u32));
+    }
+}
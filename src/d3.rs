@@ -4,19 +4,22 @@
 use anyhow::{anyhow, Result};
 use regex::Regex;
 
+use crate::{answer::Answer, solution::Solution};
+
 type Model = PartsModel;
-type Answer = i64;
 
 enum State {
     Enabled,
     Disabled,
 }
 
+#[derive(Clone)]
 pub struct PartsModel {
     part_1: Vec<Mul>,
     part_2: Vec<Mul>,
 }
 
+#[derive(Clone)]
 pub struct Mul {
     x: Option<i64>,
     y: Option<i64>,
@@ -26,52 +29,74 @@ pub struct Mul {
 
 impl Mul {
     pub fn multiply(&self) -> anyhow::Result<i64> {
-        if self.x.is_some() && self.y.is_some() {
-            return Ok(self.x.unwrap() * self.y.unwrap());
-        } else if self.x.is_some() && self.mul_y.is_some() {
-            let mul_y = self.mul_y.as_ref().unwrap();
-            if let Ok(y) = mul_y.multiply() {
-                return Ok(self.x.unwrap() * y);
-            } else {
-                return Err(anyhow!("invalid mul_y result"));
+        match (self.x, self.y, &self.mul_x, &self.mul_y) {
+            (Some(x), Some(y), _, _) => return Ok(x * y),
+            (Some(x), None, _, Some(mul_y)) => {
+                if let Ok(y) = mul_y.multiply() {
+                    return Ok(x * y);
+                } else {
+                    return Err(anyhow!("invalid mul_y result"));
+                }
             }
-        } else if self.mul_x.is_some() && self.y.is_some() {
-            let mul_x = self.mul_x.as_ref().unwrap();
-            if let Ok(x) = mul_x.multiply() {
-                return Ok(x * self.y.unwrap());
-            } else {
-                return Err(anyhow!("invalid mul_x result"));
+            (None, Some(y), Some(mul_x), _) => {
+                if let Ok(x) = mul_x.multiply() {
+                    return Ok(x * y);
+                } else {
+                    return Err(anyhow!("invalid mul_x result"));
+                }
             }
+            _ => {}
         }
 
         Err(anyhow!("invalid inputs"))
     }
 }
 
-pub fn get_muls(input: &str) -> Vec<Mul> {
+pub fn get_muls(input: &str) -> Result<Vec<Mul>> {
     let mut muls: Vec<Mul> = vec![];
 
     let re = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)").unwrap();
 
     for (_, [x, y]) in re.captures_iter(input).map(|c| c.extract()) {
         muls.push(Mul {
-            x: Some(x.parse::<i64>().unwrap()),
-            y: Some(y.parse::<i64>().unwrap()),
+            x: Some(x.parse::<i64>()?),
+            y: Some(y.parse::<i64>()?),
             mul_x: None,
             mul_y: None,
         });
     }
 
-    muls
+    Ok(muls)
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Mull It Over";
+
+    type Model = Model;
+
+    fn parse(input: String) -> Result<Model> {
+        parse(input)
+    }
+
+    fn part1(model: Model) -> Result<Answer> {
+        part1(model)
+    }
+
+    fn part2(model: Model) -> Result<Answer> {
+        part2(model)
+    }
 }
 
-pub fn parse(input: String) -> Model {
+pub fn parse(input: String) -> Result<Model> {
     let mut part_1: Vec<Mul> = vec![];
     let mut part_2: Vec<Mul> = vec![];
 
     let input = input.trim();
 
-    part_1 = get_muls(input);
+    part_1 = get_muls(input)?;
 
     let mut enabled_char_buf = String::new();
     let mut disabled_char_buf = String::new();
@@ -100,29 +125,29 @@ pub fn parse(input: String) -> Model {
         }
     }
 
-    part_2 = get_muls(&enabled_char_buf);
+    part_2 = get_muls(&enabled_char_buf)?;
 
-    PartsModel { part_1, part_2 }
+    Ok(PartsModel { part_1, part_2 })
 }
 
-pub fn part1(model: Model) -> Answer {
+pub fn part1(model: Model) -> Result<Answer> {
     let mut total: i64 = 0;
 
     for mul in model.part_1 {
-        total += mul.multiply().unwrap();
+        total += mul.multiply()?;
     }
 
-    total
+    Ok(total.into())
 }
 
-pub fn part2(model: Model) -> Answer {
+pub fn part2(model: Model) -> Result<Answer> {
     let mut total: i64 = 0;
 
     for mul in model.part_2 {
-        total += mul.multiply().unwrap();
+        total += mul.multiply()?;
     }
 
-    total
+    Ok(total.into())
 }
 
 #[cfg(test)]
@@ -1,11 +1,15 @@
 //! A solution to day 4 year 2024.
 //! https://adventofcode.com/2024/day/4
 
+use anyhow::{bail, ensure, Result};
 use regex::Regex;
+use tracing::debug;
+
+use crate::{answer::Answer, solution::Solution};
 
 type Model = Puzzle;
-type Answer = u32;
 
+#[derive(Clone)]
 pub struct Puzzle {
     input_matrix: Vec<Vec<char>>,
     horizantal: Vec<String>,
@@ -101,32 +105,65 @@ impl Puzzle {
         let total_diagnal_right = Puzzle::find_xmas(&self.diagnal_right);
         let total_diagnal_left = Puzzle::find_xmas(&self.diagnal_left);
 
-        // println!("h: {total_horizantal}");
-        // println!("v: {total_vertical}");
-        // println!("d right: {total_diagnal_right}");
-        // println!("d left: {total_diagnal_left}");
+        debug!(
+            total_horizantal,
+            total_vertical, total_diagnal_right, total_diagnal_left, "found xmas"
+        );
 
         total_horizantal + total_vertical + total_diagnal_right + total_diagnal_left
     }
 }
 
-pub fn parse(input: String) -> Model {
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 4;
+    const TITLE: &'static str = "Ceres Search";
+
+    type Model = Model;
+
+    fn parse(input: String) -> Result<Model> {
+        parse(input)
+    }
+
+    fn part1(model: Model) -> Result<Answer> {
+        part1(model)
+    }
+
+    fn part2(model: Model) -> Result<Answer> {
+        part2(model)
+    }
+}
+
+pub fn parse(input: String) -> Result<Model> {
     let lines: Vec<&str> = input.trim().split("\n").collect();
     let mut input_matrix: Vec<Vec<char>> = vec![];
 
-    for line in lines {
+    for (i, line) in lines.iter().enumerate() {
         let chars: Vec<char> = line.chars().collect();
+        // every row has to be as wide as the first for the diagonals to line up
+        if let Some(first) = input_matrix.first() {
+            if chars.len() != first.len() {
+                bail!(
+                    "line {}: expected {} letters, found {}",
+                    i + 1,
+                    first.len(),
+                    chars.len()
+                );
+            }
+        }
         input_matrix.push(chars);
     }
+    ensure!(!input_matrix[0].is_empty(), "the word search is empty");
 
-    Model::new(input_matrix)
+    Ok(Model::new(input_matrix))
 }
 
-pub fn part1(model: Model) -> Answer {
-    model.find_all()
+pub fn part1(model: Model) -> Result<Answer> {
+    Ok(model.find_all().into())
 }
 
-pub fn part2(model: Model) -> Answer {
+pub fn part2(model: Model) -> Result<Answer> {
     let mut total_x_mas = 0;
 
     for i in 1..&model.input_matrix.len() - 1 {
@@ -149,5 +186,5 @@ pub fn part2(model: Model) -> Answer {
             }
         }
     }
-    total_x_mas
+    Ok(total_x_mas.into())
 }
@@ -5,12 +5,19 @@
 
 use std::collections::{HashMap, HashSet};
 
+use anyhow::{bail, ensure, Context, Result};
 use indexmap::IndexSet;
+use tracing::{debug, trace};
+
+use crate::{
+    answer::Answer,
+    input::{column, parse_at},
+    solution::Solution,
+};
 
 type Model = UpdatePack;
-type Answer = u64;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct UpdatePack {
     rules: HashMap<u32, Rule>,
     updates: Vec<IndexSet<u32>>,
@@ -89,8 +96,10 @@ impl UpdatePack {
             // get index of this before_page_num
             if let Some(before_index) = update.get_index_of(before_page_num) {
                 if page_num_index >= before_index {
-                    // println!("update: {:?}", update);
-                    // println!("Invalid: page_num: {page_num} index: {page_num_index}, before_page_num: {before_page_num}, before_index: {before_index}");
+                    trace!(
+                        ?update,
+                        "page {page_num} at {page_num_index} must come before {before_page_num} at {before_index}"
+                    );
                     return (false, before_index, *before_page_num);
                 }
             }
@@ -110,8 +119,10 @@ impl UpdatePack {
             // get index of this before_page_num
             if let Some(after_index) = update.get_index_of(after_page_num) {
                 if page_num_index <= after_index {
-                    // println!("update: {:?}", update);
-                    // println!("Invalid: page_num: {page_num} index: {page_num_index}, after_page_num: {after_page_num}, after_index: {after_index}");
+                    trace!(
+                        ?update,
+                        "page {page_num} at {page_num_index} must come after {after_page_num} at {after_index}"
+                    );
                     return (false, after_index, *after_page_num);
                 }
             }
@@ -121,26 +132,46 @@ impl UpdatePack {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Rule {
     before: HashSet<u32>,
     after: HashSet<u32>,
 }
 
-pub fn parse(input: String) -> Model {
-    let input = input.trim();
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 5;
+    const TITLE: &'static str = "Print Queue";
+
+    type Model = Model;
+
+    fn parse(input: String) -> Result<Model> {
+        parse(input)
+    }
+
+    fn part1(model: Model) -> Result<Answer> {
+        part1(model)
+    }
+
+    fn part2(model: Model) -> Result<Answer> {
+        part2(model)
+    }
+}
 
-    // first split rules from updates
-    let parts: Vec<&str> = input.split("\n\n").collect();
+pub fn parse(input: String) -> Result<Model> {
     let mut rules: HashMap<u32, Rule> = HashMap::new();
     let mut updates: Vec<IndexSet<u32>> = vec![];
 
+    // rules come first, then a blank line, then the updates
+    let mut lines = input.trim_end().lines().enumerate();
+
     // parse out rules
-    let rule_lines: Vec<&str> = parts[0].split("\n").collect();
-    for line in rule_lines {
-        let rule_parts: Vec<&str> = line.split("|").collect();
-        let left = rule_parts[0].parse::<u32>().unwrap();
-        let right = rule_parts[1].parse::<u32>().unwrap();
+    for (i, line) in lines.by_ref() {
+        if line.is_empty() {
+            break;
+        }
+        let (left, right) = parse_rule(line).with_context(|| format!("line {}", i + 1))?;
 
         // update the before rules
         if let Some(rule) = rules.get_mut(&left) {
@@ -163,38 +194,53 @@ pub fn parse(input: String) -> Model {
         }
     }
 
-    // dbg!(&rules);
+    debug!(rules = rules.len(), "parsed rules");
+    trace!(?rules);
 
     // now parse the updates
-    let update_lines: Vec<&str> = parts[1].split("\n").collect();
-    for line in update_lines {
-        let pages: Vec<&str> = line.split(",").collect();
-        let mut update: IndexSet<u32> = IndexSet::new();
-        pages.iter().for_each(|p| {
-            let page_num = p.parse::<u32>().unwrap();
-            update.insert(page_num);
-        });
-
+    for (i, line) in lines {
+        let update = parse_update(line, &rules).with_context(|| format!("line {}", i + 1))?;
         updates.push(update);
     }
 
-    UpdatePack { rules, updates }
+    Ok(UpdatePack { rules, updates })
+}
+
+fn parse_rule(line: &str) -> Result<(u32, u32)> {
+    let Some((left, right)) = line.split_once('|') else {
+        bail!("expected a rule like 47|53, found {line:?}");
+    };
+    Ok((parse_at(line, left)?, parse_at(line, right)?))
+}
+
+/// Every page in an update needs a rule, the part functions look them up.
+fn parse_update(line: &str, rules: &HashMap<u32, Rule>) -> Result<IndexSet<u32>> {
+    let mut update: IndexSet<u32> = IndexSet::new();
+    for p in line.split(',') {
+        let page_num = parse_at(line, p)?;
+        ensure!(
+            rules.contains_key(&page_num),
+            "column {}: page {page_num} has no ordering rules",
+            column(line, p)
+        );
+        update.insert(page_num);
+    }
+    Ok(update)
 }
 
-pub fn part1(model: Model) -> Answer {
+pub fn part1(model: Model) -> Result<Answer> {
     let valid_updates = model.get_valid_updates();
     let mut total: u64 = 0;
     for update in valid_updates {
         // get middle
         let middle_index = update.len() / 2;
-        // dbg!(update);
-        // println!("{middle_index}")
+        trace!(?update, middle_index);
         total += *update.get_index(middle_index).unwrap() as u64;
     }
-    total
+    Ok(total.into())
 }
 
-pub fn part2(model: Model) -> Answer {
+pub fn part2(model: Model) -> Result<Answer> {
     let mut invalid_updates = model.get_invalid_updates();
     let mut reordered_updates: Vec<IndexSet<u32>> = vec![];
     let mut total = 0;
@@ -209,9 +255,8 @@ pub fn part2(model: Model) -> Answer {
     for update in reordered_updates {
         // get middle
         let middle_index = update.len() / 2;
-        // dbg!(update);
-        // println!("{middle_index}")
+        trace!(?update, middle_index);
         total += *update.get_index(middle_index).unwrap() as u64;
     }
-    total
+    Ok(total.into())
 }
@@ -1,52 +1,45 @@
 //! A solution to day 6 year 2024.
 //! https://adventofcode.com/2024/day/6
 
-use std::{collections::HashSet, thread, time::Duration};
-
-use crossterm::{
-    cursor::{Hide, MoveTo, Show},
-    execute,
-    style::{Color, Print, SetForegroundColor},
-    terminal::{Clear, ClearType},
-};
-use std::io::stdout;
+use std::collections::HashSet;
 
+use anyhow::{bail, ensure, Context, Result};
 use indexmap::IndexSet;
+use tracing::{debug, trace};
 
-type Model = Game;
-type Answer = usize;
-
-trait Actor {
-    fn draw(&mut self);
-    fn update(&mut self);
-}
-
-// static TICK: Option<u64> = Some(20);
-// static VISUALIZE: bool = true;
+use crate::{
+    answer::Answer,
+    input::parse_lines,
+    solution::Solution,
+    visualize::{Frame, Palette, Screen, Visualize},
+};
 
-static TICK: Option<u64> = None;
-static VISUALIZE: bool = false;
+type Model = Game;
 
+#[derive(Clone)]
 pub struct Game {
-    tick_ms: Option<u64>,
     grid: GameGrid,
     guard: Guard,
 }
 
-impl Actor for Game {
-    fn draw(&mut self) {
-        if VISUALIZE {
-            self.grid.draw();
-            self.guard.draw();
-        }
+impl Visualize for Game {
+    fn size(&self) -> (usize, usize) {
+        // the grid inside a border
+        (self.grid.width + 2, self.grid.height + 2)
     }
 
+    fn draw(&self, frame: &mut Frame) {
+        frame.border();
+        self.grid.draw(frame);
+        self.guard.draw(frame);
+    }
+}
+
+impl Game {
     fn update(&mut self) {
         // check the next grid space in guards direction for obstuction
         let (guard_x, guard_y) = self.guard.get_position_tup();
 
-        // dbg!((guard_x, guard_y));
-
         let next_space = match self.guard.direction {
             Direction::Up => self.grid.get_space(guard_x, guard_y - 1),
             Direction::Down => self.grid.get_space(guard_x, guard_y + 1),
@@ -54,7 +47,7 @@ impl Actor for Game {
             Direction::Right => self.grid.get_space(guard_x + 1, guard_y),
         };
 
-        // dbg!(&next_space);
+        trace!(guard_x, guard_y, ?next_space);
 
         match next_space {
             GridSpace::Obstructed(_) => self.guard.turn(),
@@ -64,151 +57,70 @@ impl Actor for Game {
                 self.guard.in_bounds = false
             }
         }
-
-        self.guard.update();
     }
 }
 
-impl From<String> for Game {
-    fn from(input: String) -> Self {
-        let input = input.trim().to_string();
-        let mut guard_start_position = Position { x: 0, y: 0 };
+impl TryFrom<String> for Game {
+    type Error = anyhow::Error;
 
-        let lines: Vec<&str> = input.split("\n").collect();
-        let width = lines[0].len();
-        let height = lines.len();
+    fn try_from(input: String) -> Result<Self> {
+        let mut guard_start_position = None;
+        let mut width = None;
+        let mut y = 0;
 
-        let mut grid = GameGrid::new(width, height);
+        let rows = parse_lines(&input, |line| {
+            let width = *width.get_or_insert(line.chars().count());
+            ensure!(
+                line.chars().count() == width,
+                "expected {width} columns like the first line, found {}",
+                line.chars().count()
+            );
 
-        for (y, line) in lines.iter().enumerate() {
+            let mut row = Vec::with_capacity(width);
             for (x, char) in line.chars().enumerate() {
-                match char {
-                    '.' => grid.set_space(x, y, GridSpace::Open),
-                    '#' => grid.set_space(x, y, GridSpace::Obstructed(ObsticleType::Wall)),
+                let space = match char {
+                    '.' => GridSpace::Open,
+                    '#' => GridSpace::Obstructed(ObsticleType::Wall),
+                    '^' if guard_start_position.is_some() => {
+                        bail!("column {}: a second guard", x + 1)
+                    }
                     '^' => {
-                        guard_start_position = Position {
-                            x: x as i32,
-                            y: y as i32,
-                        }
+                        guard_start_position = Some(Position { x: x as i32, y });
+                        GridSpace::Open
                     }
-                    _ => panic!("invalid input"),
-                }
+                    _ => bail!(
+                        "column {}: unexpected {char:?}, expected '.', '#', or '^'",
+                        x + 1
+                    ),
+                };
+                row.push(space);
             }
-        }
+            y += 1;
+            Ok(row)
+        })?;
+        let grid = GameGrid::from_rows(rows);
 
+        let guard_start_position =
+            guard_start_position.context("there's no guard '^' on the map")?;
         let guard = Guard::new(guard_start_position.x, guard_start_position.y);
 
-        Game {
-            tick_ms: TICK,
-            grid,
-            guard,
-        }
+        Ok(Game { grid, guard })
     }
 }
 
-impl Game {
-    pub fn draw_loop_msg() {
-        if VISUALIZE {
-            // println!("inloop");
-            let mut stdout = stdout();
-
-            // Set the cursor position
-            execute!(stdout, MoveTo(4, 5)).unwrap();
-
-            // Set the foreground color to Blue and print text
-            execute!(stdout, SetForegroundColor(Color::Magenta), Print("LOOP!"),).unwrap();
-
-            thread::sleep(Duration::from_millis(1500));
-        }
-    }
-}
-
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct GameGrid {
     width: usize,
     height: usize,
-    drawn: bool,
     grid: Vec<Vec<GridSpace>>,
 }
 
-impl Actor for GameGrid {
-    fn draw(&mut self) {
-        if !self.drawn {
-            // draw grid using crossterm
-            let border_color: Color = Color::White;
-            let obsticle_color: Color = Color::Yellow;
-            let open_color: Color = Color::DarkGrey;
-            let mut stdout = stdout();
-            let grid_size = 10;
-
-            // Define border dimensions
-            let border_width = grid_size + 2; // +2 for the left and right borders
-            let border_height = grid_size + 2; // +2 for the top and bottom borders
-
-            // Clear the screen and hide the cursor
-            execute!(stdout, Clear(ClearType::All), Hide).unwrap();
-
-            // Draw the border
-            for y in 0..border_height {
-                for x in 0..border_width {
-                    let (char_to_draw, color) = if y == 0 {
-                        // Top border
-                        if x == 0 {
-                            ("┌", border_color) // Top-left corner
-                        } else if x == border_width - 1 {
-                            ("┐", border_color) // Top-right corner
-                        } else {
-                            ("─", border_color) // Horizontal line
-                        }
-                    } else if y == border_height - 1 {
-                        // Bottom border
-                        if x == 0 {
-                            ("└", border_color) // Bottom-left corner
-                        } else if x == border_width - 1 {
-                            ("┘", border_color) // Bottom-right corner
-                        } else {
-                            ("─", border_color) // Horizontal line
-                        }
-                    } else {
-                        // Middle rows (sides only)
-                        if x == 0 || x == border_width - 1 {
-                            ("│", border_color) // Vertical line
-                        } else {
-                            // Inside the border
-                            match self.grid[y - 1][x - 1] {
-                                GridSpace::Obstructed(ObsticleType::Wall) => ("#", obsticle_color),
-                                GridSpace::Obstructed(ObsticleType::Crate) => ("0", Color::White),
-                                GridSpace::Open => (".", open_color),
-                                GridSpace::OutOfBounds => panic!("should not be in grid"),
-                            }
-                        }
-                    };
-
-                    // Set the cursor position
-                    execute!(stdout, MoveTo(x as u16, y as u16)).unwrap();
-
-                    // Set the foreground color to Blue and print text
-                    execute!(stdout, SetForegroundColor(color), Print(char_to_draw),).unwrap();
-                }
-            }
-
-            self.drawn = true;
-        }
-    }
-
-    fn update(&mut self) {
-        // currently static
-    }
-}
-
 impl GameGrid {
-    pub fn new(width: usize, height: usize) -> Self {
-        let grid = vec![vec![GridSpace::Open; width]; height];
+    pub fn from_rows(grid: Vec<Vec<GridSpace>>) -> Self {
         GameGrid {
-            drawn: false,
+            width: grid.first().map_or(0, Vec::len),
+            height: grid.len(),
             grid,
-            width,
-            height,
         }
     }
 
@@ -224,9 +136,24 @@ impl GameGrid {
     pub fn set_space(&mut self, x: usize, y: usize, space: GridSpace) {
         self.grid[y][x] = space;
     }
+
+    /// Draw the grid inside the frame's border.
+    fn draw(&self, frame: &mut Frame) {
+        for (y, row) in self.grid.iter().enumerate() {
+            for (x, space) in row.iter().enumerate() {
+                let (ch, color) = match space {
+                    GridSpace::Obstructed(ObsticleType::Wall) => ('#', Palette::Wall),
+                    GridSpace::Obstructed(ObsticleType::Crate) => ('0', Palette::Marker),
+                    GridSpace::Open => ('.', Palette::Dim),
+                    GridSpace::OutOfBounds => panic!("should not be in grid"),
+                };
+                frame.set(x + 1, y + 1, ch, color);
+            }
+        }
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Guard {
     start_position: Position,
     position: Position,
@@ -238,35 +165,6 @@ struct Guard {
     track_path: bool,
 }
 
-impl Actor for Guard {
-    fn draw(&mut self) {
-        let mut stdout = stdout();
-        let x = self.position.x as u16;
-        let y = self.position.y as u16;
-        let direction_char = match self.direction {
-            Direction::Up => "^",
-            Direction::Down => "V",
-            Direction::Left => "<",
-            Direction::Right => ">",
-        };
-
-        // Set the cursor position
-        execute!(stdout, MoveTo(x + 1, y + 1)).unwrap();
-
-        // Set the foreground color to Blue and print text
-        execute!(
-            stdout,
-            SetForegroundColor(Color::Cyan),
-            Print(direction_char),
-        )
-        .unwrap();
-    }
-
-    fn update(&mut self) {
-        // update guard
-    }
-}
-
 impl Guard {
     pub fn new(x: i32, y: i32) -> Self {
         let position = Position { x, y };
@@ -312,10 +210,7 @@ impl Guard {
     }
 
     fn turn(&mut self) {
-        // println!(
-        //     "turn(): current pos: {:?}, current dir: {:?}",
-        //     self.position, self.direction
-        // );
+        trace!(position = ?self.position, direction = ?self.direction, "turn");
 
         // before turning record our position and direction we were going that made us turn for loop detection
         if self
@@ -340,6 +235,23 @@ impl Guard {
     fn is_start_position(&self, x: i32, y: i32) -> bool {
         self.start_position.x == x && self.start_position.y == y
     }
+
+    /// Draw the guard facing its direction, inside the frame's border.
+    fn draw(&self, frame: &mut Frame) {
+        let ch = match self.direction {
+            Direction::Up => '^',
+            Direction::Down => 'V',
+            Direction::Left => '<',
+            Direction::Right => '>',
+        };
+        // a guard that has left the grid isn't drawn
+        if let (Ok(x), Ok(y)) = (
+            usize::try_from(self.position.x + 1),
+            usize::try_from(self.position.y + 1),
+        ) {
+            frame.set(x, y, ch, Palette::Actor);
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
@@ -369,61 +281,53 @@ enum ObsticleType {
     Crate,
 }
 
-// make sure cursor is always shown at the end
-struct CursorGuard;
+pub struct Solver;
 
-impl Drop for CursorGuard {
-    fn drop(&mut self) {
-        if VISUALIZE {
-            let mut stdout = stdout();
+impl Solution for Solver {
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "Guard Gallivant";
 
-            // Ensure the cursor is shown when the program exits
-            execute!(stdout, Show).unwrap();
+    type Model = Model;
 
-            // move cursor to bottom
-            execute!(stdout, MoveTo(0, 12)).unwrap();
-        }
+    fn parse(input: String) -> Result<Model> {
+        parse(input)
+    }
+
+    fn part1(model: Model) -> Result<Answer> {
+        part1(model)
+    }
+
+    fn part2(model: Model) -> Result<Answer> {
+        part2(model)
     }
 }
 
-pub fn parse(input: String) -> Model {
-    Game::from(input)
+pub fn parse(input: String) -> Result<Model> {
+    Game::try_from(input)
 }
 
-pub fn part1(model: Model) -> Answer {
+pub fn part1(model: Model) -> Result<Answer> {
     let mut model = model;
-
-    // Create a guard to ensure the cursor is shown when exiting
-    let _guard = CursorGuard;
+    let mut screen = Screen::start();
 
     // game loop
     while model.guard.in_bounds {
-        model.draw();
+        screen.show(&model)?;
         model.update();
-
-        if let Some(tick_ms) = model.tick_ms {
-            thread::sleep(Duration::from_millis(tick_ms));
-        }
     }
 
-    model.guard.traveled_path.len() - 1
+    Ok((model.guard.traveled_path.len() - 1).into())
 }
 
-pub fn part2(model: Model) -> Answer {
+pub fn part2(model: Model) -> Result<Answer> {
     let mut model = model;
     let mut num_positions = 0;
-
-    // Create a guard to ensure the cursor is shown when exiting
-    let _guard = CursorGuard;
+    let mut screen = Screen::start();
 
     // calculate the first path travelled, only new obsticles should go in this path
     while model.guard.in_bounds {
-        model.draw();
+        screen.show(&model)?;
         model.update();
-
-        if let Some(tick_ms) = model.tick_ms {
-            thread::sleep(Duration::from_millis(tick_ms));
-        }
     }
 
     // starting position and remove the last out of bounds position in the path
@@ -436,7 +340,7 @@ pub fn part2(model: Model) -> Answer {
     model.guard.track_path = false; // tracking path takes a lot of resources to do the IndexSet inserts, it's not needed for finding the loops
 
     for (x, y) in traveled_path {
-        // println!("set O: ({}, {})", x, y);
+        trace!(x, y, "placing a crate");
 
         if model.guard.is_start_position(x, y) {
             // skip the starting position
@@ -451,19 +355,13 @@ pub fn part2(model: Model) -> Answer {
         );
 
         while model.guard.in_bounds && !model.guard.in_loop {
-            // println!("update");
-            model.draw();
+            screen.show(&model)?;
             model.update();
-
-            // println!("({}, {})", model.guard.position.x, model.guard.position.y);
-
-            if let Some(tick_ms) = model.tick_ms {
-                thread::sleep(Duration::from_millis(tick_ms));
-            }
         }
 
         if model.guard.in_loop {
-            Game::draw_loop_msg();
+            debug!(x, y, "a crate here puts the guard in a loop");
+            screen.flash("LOOP!", Palette::Alert)?;
             num_positions += 1;
         }
 
@@ -473,11 +371,9 @@ pub fn part2(model: Model) -> Answer {
         model
             .grid
             .set_space(x as usize, y as usize, GridSpace::Open);
-
-        model.grid.drawn = false;
     }
 
-    num_positions
+    Ok(num_positions.into())
 }
 
 #[cfg(test)]
@@ -492,7 +388,7 @@ mod tests {
 ##.";
 
         // Convert input into a Game instance
-        let game: Game = Game::from(input.to_string());
+        let game: Game = Game::try_from(input.to_string()).unwrap();
 
         // Verify grid initialization
         assert_eq!(
@@ -516,4 +412,17 @@ mod tests {
         assert_eq!(game.guard.position.x, 1); // Guard is at column 1
         assert_eq!(game.guard.position.y, 1); // Guard is at row 1
     }
+
+    #[test]
+    fn test_game_errors_point_at_the_input() {
+        let err = Game::try_from("..#\n.^.\n#^.\n".to_string()).err().unwrap();
+        assert_eq!(format!("{err:#}"), "line 3: column 2: a second guard");
+    }
+
+    #[test]
+    fn test_game_after_blank_lines() {
+        let game = Game::try_from("\n\n..#\n.^.\n##.\n".to_string()).unwrap();
+        assert_eq!((game.grid.width, game.grid.height), (3, 3));
+        assert_eq!((game.guard.position.x, game.guard.position.y), (1, 1));
+    }
 }
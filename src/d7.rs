@@ -1,60 +1,61 @@
 //! A solution to day 7 year 2024.
 //! https://adventofcode.com/2024/day/7
 
-use std::sync::atomic::{AtomicU64, Ordering};
+use anyhow::{ensure, Context, Result};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use tracing::trace;
 
-use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
+use crate::{
+    answer::Answer,
+    input::{parse_at, parse_lines},
+    solution::Solution,
+};
 
 type Model = Vec<Calibration>;
-type Answer = u64;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Calibration {
     value: u64,
     operands: Vec<u32>,
 }
 
-impl From<String> for Calibration {
-    fn from(input: String) -> Self {
-        let parts: Vec<&str> = input.split(":").collect();
-        let value = parts[0].trim().parse::<u64>().unwrap();
-        let operands: Vec<&str> = parts[1].trim().split(" ").collect();
-        let mut operands: Vec<u32> = operands
-            .iter()
-            .map(|o| o.trim().parse::<u32>().unwrap())
-            .collect();
-
-        // reverse to read right-to-left using pop() which is faster than remove(0)
-        operands.reverse();
-
-        Calibration { value, operands }
+impl TryFrom<String> for Calibration {
+    type Error = anyhow::Error;
+
+    fn try_from(input: String) -> Result<Self> {
+        let (value, operands) = input
+            .split_once(":")
+            .with_context(|| format!("expected a calibration like 190: 10 19, found {input:?}"))?;
+        let value = parse_at::<u64>(&input, value.trim())?;
+        let operands: Vec<u32> = operands
+            .split_whitespace()
+            .map(|o| parse_at(&input, o))
+            .collect::<Result<_>>()?;
+        ensure!(
+            !operands.is_empty(),
+            "a calibration needs at least one operand"
+        );
+
+        Ok(Calibration { value, operands })
     }
 }
 
 impl Calibration {
-    fn calculate_value(&self, operators: &mut Vec<char>) -> u64 {
-        let mut value = 0u64;
-        let mut operands = self.operands.clone();
-
-        // reverse to read right-to-left using pop() which is faster than remove(0)
-        operators.reverse();
-
-        while !operands.is_empty() {
-            let left_operand = if value == 0 {
-                operands.pop().unwrap() as u64
-            } else {
-                value
-            };
-            let next_operator = operators.pop().unwrap();
-            let right_operand = operands.pop().unwrap();
-
-            value = match next_operator {
-                '*' => left_operand * right_operand as u64,
-                '+' => left_operand + right_operand as u64,
-                '|' => {
-                    let concat_value_string = format!("{left_operand}{right_operand}");
-                    concat_value_string.parse::<u64>().unwrap()
-                }
+    /// Apply the operators left to right, one between each pair of operands.  A single operand
+    /// is its own value.  None when the value doesn't fit in a u64, so it can't be the
+    /// calibration's.
+    fn calculate_value(&self, operators: &[char]) -> Option<u64> {
+        let mut value = Some(self.operands[0] as u64);
+
+        for (&operator, &operand) in operators.iter().zip(&self.operands[1..]) {
+            let operand = operand as u64;
+            value = match (value, operator) {
+                // even a value too big for a u64 comes back down to 0
+                (_, '*') if operand == 0 => Some(0),
+                (None, _) => None,
+                (Some(value), '*') => value.checked_mul(operand),
+                (Some(value), '+') => value.checked_add(operand),
+                (Some(value), '|') => concat(value, operand),
                 _ => panic!("unknown operator"),
             };
         }
@@ -63,6 +64,12 @@ impl Calibration {
     }
 }
 
+/// The digits of `left` followed by the digits of `right`, or None if that doesn't fit in a u64.
+fn concat(left: u64, right: u64) -> Option<u64> {
+    let digits = right.checked_ilog10().unwrap_or(0) + 1;
+    left.checked_mul(10u64.pow(digits))?.checked_add(right)
+}
+
 pub fn permute_with_two(size: usize) -> Vec<Vec<char>> {
     if size == 0 {
         return vec![vec![]];
@@ -109,65 +116,66 @@ pub fn permute_with_three(size: usize) -> Vec<Vec<char>> {
     result
 }
 
-pub fn parse(input: String) -> Model {
-    let input = input.trim();
-    let mut calibrations: Vec<Calibration> = vec![];
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 7;
+    const TITLE: &'static str = "Bridge Repair";
+
+    type Model = Model;
+
+    fn parse(input: String) -> Result<Model> {
+        parse(input)
+    }
+
+    fn part1(model: Model) -> Result<Answer> {
+        part1(model)
+    }
 
-    let lines: Vec<&str> = input.split("\n").collect();
-    for line in lines {
-        let calibration = Calibration::from(line.to_string());
-        calibrations.push(calibration);
+    fn part2(model: Model) -> Result<Answer> {
+        part2(model)
     }
+}
 
-    calibrations
+pub fn parse(input: String) -> Result<Model> {
+    parse_lines(&input, |line| Calibration::try_from(line.to_string()))
 }
 
-pub fn part1(model: Model) -> Answer {
+pub fn part1(model: Model) -> Result<Answer> {
     let mut sum = 0;
-    let mut model = model;
 
-    for calibration in model.iter_mut() {
-        let operators_len = calibration.operands.len() - 1;
-        let mut operators = permute_with_two(operators_len);
+    for calibration in &model {
+        let operators = permute_with_two(calibration.operands.len() - 1);
 
         // see if any of the operator permutations yields the correct value
-        for ops in operators.iter_mut() {
-            let value = calibration.calculate_value(ops);
-            if calibration.value == value {
-                // println!("correct: {} == {}", calibration.value, value);
-                sum += value;
-                break; // found a good combination, no need to keep going
-            } else {
-                // println!("incorrect: {} == {}", calibration.value, value);
-            }
+        let solvable = operators
+            .iter()
+            .any(|ops| calibration.calculate_value(ops) == Some(calibration.value));
+        trace!(value = calibration.value, solvable);
+        if solvable {
+            sum += calibration.value;
         }
     }
 
-    sum
+    Ok(sum.into())
 }
 
-pub fn part2(model: Model) -> Answer {
-    let sum = AtomicU64::new(0);
-    let mut model = model;
+pub fn part2(model: Model) -> Result<Answer> {
+    let mut sum = 0;
 
-    for calibration in model.iter_mut() {
-        let operators_len = calibration.operands.len() - 1;
-        let mut operators = permute_with_three(operators_len);
+    for calibration in &model {
+        let operators = permute_with_three(calibration.operands.len() - 1);
 
-        let _ = operators.par_iter_mut().try_for_each(|o| {
-            let value = calibration.calculate_value(o);
-            if calibration.value == value {
-                // println!("correct: {} == {}", calibration.value, value);
-                sum.fetch_add(value, Ordering::Relaxed);
-                Err(()) // found a good combination, break out of the loop
-            } else {
-                // println!("incorrect: {} == {}", calibration.value, value);
-                Ok(()) // continue the loop
-            }
-        });
+        let solvable = operators
+            .par_iter()
+            .any(|ops| calibration.calculate_value(ops) == Some(calibration.value));
+        trace!(value = calibration.value, solvable);
+        if solvable {
+            sum += calibration.value;
+        }
     }
 
-    sum.load(Ordering::Relaxed)
+    Ok(sum.into())
 }
 
 #[cfg(test)]
@@ -177,12 +185,43 @@ mod tests {
     #[test]
     fn test_calibration_from_string() {
         let input = "190: 10 19".to_string();
-        let calibration: Calibration = input.into();
+        let calibration: Calibration = input.try_into().unwrap();
 
         assert_eq!(calibration.value, 190);
         assert_eq!(calibration.operands, vec![10, 19]);
     }
 
+    #[test]
+    fn test_calculate_value() {
+        let calibration: Calibration = "7290: 6 8 6 15".to_string().try_into().unwrap();
+        assert_eq!(calibration.calculate_value(&['*', '|', '*']), Some(7290));
+
+        let single: Calibration = "5: 5".to_string().try_into().unwrap();
+        assert_eq!(single.calculate_value(&[]), Some(5));
+
+        let zeros: Calibration = "0: 0 0 0".to_string().try_into().unwrap();
+        assert_eq!(zeros.calculate_value(&['+', '|']), Some(0));
+
+        let huge: Calibration = "1: 4294967295 4294967295 4294967295"
+            .to_string()
+            .try_into()
+            .unwrap();
+        assert_eq!(huge.calculate_value(&['*', '*']), None);
+        assert_eq!(huge.calculate_value(&['|', '|']), None);
+        let comes_back: Calibration = "0: 4294967295 4294967295 4294967295 4294967295 0"
+            .to_string()
+            .try_into()
+            .unwrap();
+        assert_eq!(comes_back.calculate_value(&['*', '*', '*', '*']), Some(0));
+    }
+
+    #[test]
+    fn test_unusual_calibrations() {
+        let model = parse("5: 5\n0: 0 0 0\n6: 2\n".to_string()).unwrap();
+        assert_eq!(part1(model.clone()).unwrap(), Answer::from(5u64));
+        assert_eq!(part2(model).unwrap(), Answer::from(5u64));
+    }
+
     #[test]
     fn test_permute_length_1() {
         let result = permute_with_two(1);
@@ -3,50 +3,69 @@
 
 use std::collections::{HashMap, HashSet};
 
+use anyhow::{ensure, Result};
 use geo::Point;
+use tracing::trace;
+
+use crate::{answer::Answer, input::parse_lines, solution::Solution};
 
 type Model = CityMap;
-type Answer = usize;
 type Frequency = char;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CityMap {
     width: u16,
     height: u16,
     antennas: HashMap<Frequency, Vec<Point<i16>>>,
 }
 
-impl From<String> for CityMap {
-    fn from(input: String) -> Self {
-        let input = input.trim();
-        let mut lines: Vec<&str> = input.split("\n").collect();
-        let mut antennas: HashMap<Frequency, Vec<Point<i16>>> = HashMap::new();
-        let width = lines[0].len() as u16;
-        let height = lines.len() as u16;
+impl TryFrom<String> for CityMap {
+    type Error = anyhow::Error;
 
-        lines.reverse();
+    fn try_from(input: String) -> Result<Self> {
+        let mut width = None;
+        let rows = parse_lines(&input, |line| {
+            let width = *width.get_or_insert(line.len());
+            ensure!(
+                line.len() == width,
+                "expected {width} columns like the first line, found {}",
+                line.len()
+            );
 
-        for (y, line) in lines.iter().enumerate() {
+            let mut row = vec![];
             for (x, freq) in line.chars().enumerate() {
-                if freq != '.' {
-                    let point = Point::new(x as i16, y as i16);
-
-                    // add this antenna location for it's frequency
-                    if let Some(frequency) = antennas.get_mut(&freq) {
-                        frequency.push(point);
-                    } else {
-                        // add this frequency and it's first point
-                        antennas.insert(freq, vec![point]);
-                    }
+                if freq == '.' {
+                    continue;
                 }
+                ensure!(
+                    freq.is_ascii_alphanumeric(),
+                    "column {}: unexpected {freq:?}, expected '.' or an antenna frequency",
+                    x + 1
+                );
+                row.push((x, freq));
+            }
+            Ok(row)
+        })?;
+        let width = width.unwrap_or(0) as u16;
+        let height = rows.len() as u16;
+
+        let mut antennas: HashMap<Frequency, Vec<Point<i16>>> = HashMap::new();
+        // y counts up from the bottom, the input's lines count down from the top
+        for (y, row) in rows.iter().rev().enumerate() {
+            for &(x, freq) in row {
+                // add this antenna location for it's frequency
+                antennas
+                    .entry(freq)
+                    .or_default()
+                    .push(Point::new(x as i16, y as i16));
             }
         }
 
-        CityMap {
+        Ok(CityMap {
             width,
             height,
             antennas,
-        }
+        })
     }
 }
 
@@ -360,7 +379,7 @@ impl AntennaLine {
                     if next_antinode.is_some() {
                         next_antinode = self.step_left_in_bounds(next_antinode.unwrap());
                         if let Some(node) = next_antinode {
-                            // println!("next left step: {:?}", node);
+                            trace!(?node, "next left step");
                             antinodes.push(node);
                         } else {
                             break;
@@ -374,7 +393,7 @@ impl AntennaLine {
                     if next_antinode.is_some() {
                         next_antinode = self.step_right_in_bounds(next_antinode.unwrap());
                         if let Some(node) = next_antinode {
-                            // println!("next right step: {:?}", node);
+                            trace!(?node, "next right step");
                             antinodes.push(node);
                         } else {
                             break;
@@ -389,7 +408,7 @@ impl AntennaLine {
                     if next_antinode.is_some() {
                         next_antinode = self.step_up_in_bounds(next_antinode.unwrap());
                         if let Some(node) = next_antinode {
-                            // println!("next up step: {:?}", node);
+                            trace!(?node, "next up step");
                             antinodes.push(node);
                         } else {
                             break;
@@ -403,7 +422,7 @@ impl AntennaLine {
                     if next_antinode.is_some() {
                         next_antinode = self.step_down_in_bounds(next_antinode.unwrap());
                         if let Some(node) = next_antinode {
-                            // println!("next down step: {:?}", node);
+                            trace!(?node, "next down step");
                             antinodes.push(node);
                         } else {
                             break;
@@ -422,39 +441,56 @@ fn calc_slope(p1: Point<i16>, p2: Point<i16>) -> f64 {
     (p2.y() as f64 - p1.y() as f64) / (p2.x() as f64 - p1.x() as f64)
 }
 
-pub fn parse(input: String) -> CityMap {
-    CityMap::from(input)
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 8;
+    const TITLE: &'static str = "Resonant Collinearity";
+
+    type Model = Model;
+
+    fn parse(input: String) -> Result<Model> {
+        parse(input)
+    }
+
+    fn part1(model: Model) -> Result<Answer> {
+        part1(model)
+    }
+
+    fn part2(model: Model) -> Result<Answer> {
+        part2(model)
+    }
+}
+
+pub fn parse(input: String) -> Result<Model> {
+    CityMap::try_from(input)
 }
 
-pub fn part1(model: Model) -> Answer {
-    // dbg!(model);
+pub fn part1(model: Model) -> Result<Answer> {
     let lines = model.find_unique_antenna_lines();
     let mut unique_anti_coords: HashSet<&Point<i16>> = HashSet::new();
 
     for line in lines.values().flatten() {
-        // dbg!(&line);
+        trace!(?line);
         //TODO: refactor this to get first element of antinodes vec
         if model.is_point_in_bounds(line.anti1) {
-            // println!("in bounds: ({},{})", line.anti1.x(), line.anti1.y());
             unique_anti_coords.insert(&line.anti1);
         } else {
-            // println!("out of bounds: ({},{})", line.anti1.x(), line.anti1.y());
+            trace!(antinode = ?line.anti1, "out of bounds");
         }
 
         //TODO: refactor this to get second element of antinodes vec
         if model.is_point_in_bounds(line.anti2) {
-            // println!("in bounds: ({},{})", line.anti2.x(), line.anti2.y());
             unique_anti_coords.insert(&line.anti2);
         } else {
-            // println!("out of bounds: ({},{})", line.anti2.x(), line.anti2.y());
+            trace!(antinode = ?line.anti2, "out of bounds");
         }
     }
 
-    unique_anti_coords.len()
+    Ok(unique_anti_coords.len().into())
 }
 
-pub fn part2(model: Model) -> Answer {
-    // dbg!(model);
+pub fn part2(model: Model) -> Result<Answer> {
     let lines = model.find_unique_antenna_lines();
     let mut unique_anti_coords: HashSet<Point<i16>> = HashSet::new();
 
@@ -471,7 +507,7 @@ pub fn part2(model: Model) -> Answer {
         }
     }
 
-    unique_anti_coords.len()
+    Ok(unique_anti_coords.len().into())
 }
 
 #[cfg(test)]
@@ -500,9 +536,24 @@ mod tests {
         assert_eq!(unique_pairs.len(), 1);
     }
 
+    #[test]
+    fn test_city_map_errors_point_at_the_input() {
+        let err = CityMap::try_from("..a\n.?.\n".to_string()).unwrap_err();
+        assert_eq!(
+            format!("{err:#}"),
+            "line 2: column 2: unexpected '?', expected '.' or an antenna frequency"
+        );
+    }
+
+    #[test]
+    fn test_city_map_after_blank_lines() {
+        let city_map = CityMap::try_from(format!("\n{EXAMPLE}")).unwrap();
+        assert_eq!((city_map.width, city_map.height), (12, 12));
+    }
+
     #[test]
     fn test_is_point_in_bounds() {
-        let city_map = CityMap::from(EXAMPLE.to_string());
+        let city_map = CityMap::try_from(EXAMPLE.to_string()).unwrap();
 
         // Test points within bounds
         assert!(city_map.is_point_in_bounds(Point::new(0, 0))); // Bottom-left corner
@@ -540,7 +591,7 @@ mod tests {
 
     #[test]
     fn test_find_unique_point_pairs_example() {
-        let city_map = CityMap::from(EXAMPLE.to_string());
+        let city_map = CityMap::try_from(EXAMPLE.to_string()).unwrap();
 
         let result_pairs = find_unique_point_pairs(city_map.antennas.get(&'0').unwrap());
 
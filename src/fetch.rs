@@ -0,0 +1,117 @@
+//! `aoc2024 fetch`, which downloads the days' inputs, and optionally their first example, from
+//! the Advent of Code site.
+//!
+//! Everything downloaded is kept: inputs in `input/dN` and puzzle pages in `.cache/`, so
+//! running it again only asks the site for what's missing.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+use anyhow::{bail, Result};
+
+use crate::{
+    args::FetchArgs,
+    site::{self, strip_tags, unescape, write, Http, Site, Ureq, CACHE_DIR},
+};
+
+/// Download a day's input to `input/dN`, unless it's there already.
+fn input(site: &mut Site<impl Http>, day: u8, force: bool) -> Result<()> {
+    let path = PathBuf::from(format!("input/d{day}"));
+    if path.exists() && !force {
+        println!("input for day {day} already exists in {}", path.display());
+        return Ok(());
+    }
+
+    let input = site.get(&format!("day/{day}/input"))?;
+    write(&path, &input)?;
+    println!("input saved: {}", path.display());
+    Ok(())
+}
+
+/// Save the first example on a day's puzzle page to `examples/dN`, unless there's already an
+/// example there.  An empty file, like the one `scaffold` creates, doesn't count.
+fn example(site: &mut Site<impl Http>, day: u8, force: bool) -> Result<()> {
+    let path = PathBuf::from(format!("examples/d{day}"));
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+    if !existing.is_empty() && !force {
+        println!("example for day {day} already exists in {}", path.display());
+        return Ok(());
+    }
+
+    let page = page(site, day, force)?;
+    let Some(example) = first_example(&page) else {
+        bail!("no example found on the puzzle page for day {day}");
+    };
+    write(&path, &example)?;
+    println!("example saved: {}", path.display());
+    Ok(())
+}
+
+/// A day's puzzle page, from the cache when it's there.  Part 2 only appears on the page once
+/// part 1 is solved, but the first example is always in part 1, so a cached page never goes
+/// stale for that.
+fn page(site: &mut Site<impl Http>, day: u8, force: bool) -> Result<String> {
+    let cached = Path::new(CACHE_DIR).join(format!("{}-d{day}.html", site.year));
+    if !force {
+        if let Ok(page) = fs::read_to_string(&cached) {
+            return Ok(page);
+        }
+    }
+
+    let page = site.get(&format!("day/{day}"))?;
+    write(&cached, &page)?;
+    Ok(page)
+}
+
+/// The text of the first `<pre><code>` block on a puzzle page, which is nearly always the
+/// example input.
+fn first_example(page: &str) -> Option<String> {
+    let start = page.find("<pre><code>")? + "<pre><code>".len();
+    let len = page[start..].find("</code></pre>")?;
+    Some(unescape(&strip_tags(&page[start..start + len])))
+}
+
+pub fn fetch(args: &FetchArgs) {
+    let session = site::session().unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        exit(1);
+    });
+
+    let mut site = Site::new(Ureq::new(), &args.base_url, args.year, session);
+    let mut failed = false;
+    for &day in &args.days.0 {
+        let mut result = input(&mut site, day, args.force);
+        if args.example {
+            // no point asking for the puzzle page when the input wasn't there
+            result = result.and_then(|()| example(&mut site, day, args.force));
+        }
+        if let Err(e) = result {
+            eprintln!("Error: day {day}: {e:#}");
+            failed = true;
+        }
+    }
+
+    if failed {
+        exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_first_example() {
+        let page = "<article><p>For example:</p>\n\
+            <pre><code>190: 10 19\n<em>3267</em>: 81 40 27\na &lt;b&gt; &amp;lt;\n</code></pre>\n\
+            <pre><code>second</code></pre></article>";
+        assert_eq!(
+            first_example(page).as_deref(),
+            Some("190: 10 19\n3267: 81 40 27\na <b> &lt;\n")
+        );
+        assert_eq!(first_example("<p>no examples</p>"), None);
+    }
+}
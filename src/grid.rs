@@ -62,7 +62,7 @@ impl<T: Copy> Grid<T> {
     ///
     /// Four cells will always be returned, in the following order relative to the given point:
     ///  
-    /// ```
+    /// ```text
     /// [ ⬆️, ⬅️, ➡️, ⬇️, ]
     /// ```
     ///
@@ -96,7 +96,7 @@ impl<T: Copy> Grid<T> {
     ///
     /// Eight cells will always be returned, in the following order relative to the given point:
     ///  
-    /// ```
+    /// ```text
     /// [
     ///   ↖️, ⬆️, ↗️,
     ///   ⬅️,    ➡️,
@@ -0,0 +1,67 @@
+//! Helpers for turning bad puzzle input into errors that point at the offending text.
+//!
+//! Errors read like `line 3: column 6: can't parse "1x": invalid digit found in string` when
+//! printed with `{:#}`, which is how the runner prints them.
+
+use std::{error::Error, str::FromStr};
+
+use anyhow::{Context, Result};
+
+/// The 1-based column where `part` starts in `line`.
+///
+/// Panics if `part` isn't a slice of `line`.
+pub fn column(line: &str, part: &str) -> usize {
+    let offset = (part.as_ptr() as usize)
+        .checked_sub(line.as_ptr() as usize)
+        .filter(|offset| offset + part.len() <= line.len())
+        .expect("part must be a slice of line");
+    line[..offset].chars().count() + 1
+}
+
+/// Parse `part`, a slice of `line`, with an error naming its column when it isn't valid.
+pub fn parse_at<T>(line: &str, part: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: Error + Send + Sync + 'static,
+{
+    part.parse()
+        .with_context(|| format!("column {}: can't parse {part:?}", column(line, part)))
+}
+
+/// Parse each non-blank line of `input` with `f`, adding the line number to any error.
+pub fn parse_lines<T>(input: &str, mut f: impl FnMut(&str) -> Result<T>) -> Result<Vec<T>> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| f(line).with_context(|| format!("line {}", i + 1)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column_of_a_slice() {
+        let line = "190: 10 19";
+        assert_eq!(column(line, &line[..3]), 1);
+        assert_eq!(column(line, &line[8..]), 9);
+    }
+
+    #[test]
+    fn errors_point_at_the_input() {
+        let input = "1 2\n\n3 x4\n";
+        let err = parse_lines(input, |line| {
+            line.split(' ')
+                .map(|n| parse_at::<u8>(line, n))
+                .collect::<Result<Vec<_>>>()
+        })
+        .unwrap_err();
+
+        assert_eq!(
+            format!("{err:#}"),
+            "line 3: column 3: can't parse \"x4\": invalid digit found in string"
+        );
+    }
+}
@@ -1,5 +1,7 @@
 #![allow(clippy::let_and_return)]
 #![allow(unused)]
+pub mod answer;
+pub mod answers;
 pub mod d1;
 pub mod d10;
 pub mod d11;
@@ -27,4 +29,118 @@ pub mod d8;
 pub mod d9;
 pub mod direction;
 pub mod grid;
+pub mod input;
+pub mod memory;
 pub mod point;
+#[cfg(test)]
+mod regression;
+pub mod solution;
+pub mod visualize;
+
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+
+use answer::Answer;
+use solution::Day;
+
+/// Every registered day, in order.  Adding a day means adding its `Solver` here.
+pub static DAYS: &[&dyn Day] = &[
+    &d1::Solver,
+    &d2::Solver,
+    &d3::Solver,
+    &d4::Solver,
+    &d5::Solver,
+    &d6::Solver,
+    &d7::Solver,
+    &d8::Solver,
+    &d9::Solver,
+    &d10::Solver,
+    &d11::Solver,
+    &d12::Solver,
+    &d13::Solver,
+    &d14::Solver,
+    &d15::Solver,
+    &d16::Solver,
+    &d17::Solver,
+    &d18::Solver,
+    &d19::Solver,
+    &d20::Solver,
+    &d21::Solver,
+    &d22::Solver,
+    &d23::Solver,
+    &d24::Solver,
+    &d25::Solver,
+];
+
+/// Look up a registered day by its number.
+pub fn get_day(day: u8) -> Option<&'static dyn Day> {
+    DAYS.iter().copied().find(|d| d.day() == day)
+}
+
+/// The answer to one part of a day, from [`solve`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solved {
+    pub answer: Answer,
+    pub parse_time: Duration,
+    /// how long the part took, not including the parse
+    pub solve_time: Duration,
+}
+
+/// Solve one part of a day's puzzle for the given input, for tools that link this crate instead
+/// of running the binary.
+///
+/// Fails if the day isn't registered, the part isn't 1 or 2, or the solution returns an error or
+/// panics.  Panics are caught, so a bad input can't take the caller down with it.
+///
+/// ```
+/// use aoc2024::answer::Answer;
+///
+/// let input = std::fs::read_to_string("examples/d7")?;
+/// let solved = aoc2024::solve(7, 1, &input)?;
+/// assert_eq!(solved.answer, Answer::Int(3749));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn solve(day: u8, part: u8, input: &str) -> Result<Solved> {
+    let Some(solution) = get_day(day) else {
+        bail!("day {day} isn't implemented");
+    };
+    if ![1, 2].contains(&part) {
+        bail!("part must be 1 or 2, not {part}");
+    }
+
+    let result = solution.run(input.to_string(), &[part]);
+    let [part_result] = &result.parts[..] else {
+        unreachable!("one part was run");
+    };
+    let answer = part_result
+        .answer
+        .clone()
+        .with_context(|| format!("day {day} part {part} failed"))?;
+
+    Ok(Solved {
+        answer,
+        parse_time: result.parse_time,
+        solve_time: part_result.time,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_rejects_unknown_days_and_parts() {
+        assert!(solve(26, 1, "").is_err());
+        assert!(solve(7, 3, "").is_err());
+    }
+
+    #[test]
+    fn solve_reports_failures() {
+        let err = solve(7, 1, "190: x").unwrap_err();
+        assert_eq!(
+            format!("{err:#}"),
+            "day 7 part 1 failed: parse error: line 1: column 6: can't parse \"x\": invalid digit found in string"
+        );
+    }
+}
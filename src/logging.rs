@@ -0,0 +1,27 @@
+//! Diagnostic logs on stderr, from the solutions and the runner, so they never mix with the
+//! answers on stdout.
+//!
+//! Each day logs under its own target, like `aoc2024::d6`.  `-v` shows every target's debug
+//! logs and `-vv` their trace logs too, while `RUST_LOG` can pick out targets, like
+//! `RUST_LOG=aoc2024::d6=trace`.
+
+use std::io;
+
+use tracing_subscriber::{filter::LevelFilter, EnvFilter};
+
+/// Log at the verbosity from `-v` or `-q`, unless `RUST_LOG` says otherwise.  Libraries only get
+/// to log warnings, however verbose it is.
+pub fn init(verbosity: LevelFilter) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+        EnvFilter::new(format!(
+            "{},aoc2024={verbosity}",
+            verbosity.min(LevelFilter::WARN)
+        ))
+    });
+
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(io::stderr)
+        .without_time()
+        .init();
+}
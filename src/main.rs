@@ -1,336 +1,421 @@
 mod args;
+mod bench;
+mod fetch;
+mod logging;
+mod output;
+mod scaffold;
+mod serve;
+mod site;
+mod submit;
+mod watch;
 
-use std::{fmt::Display, fs::read_to_string, process::exit, time::Instant};
+use std::{
+    collections::BTreeMap,
+    fs::{self, read_to_string},
+    io::{self, IsTerminal, Read},
+    path::{Path, PathBuf},
+    process::exit,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
 
-use termion::{color, style};
+use aoc2024::{
+    answers::{Answers, ANSWERS_DIR},
+    solution::DayResult,
+    visualize,
+};
+use args::{Args, Command, Example, Format};
+use bench::{bench_day, load_baseline, print_measurement, save_baseline, Baseline};
+use output::{
+    print_day, print_header, print_part, print_totals, print_wall_clock, Record, Summary,
+};
+use tracing::debug;
 
-fn main() {
-    let args = args::parse_args().unwrap_or_else(|_| {
-        eprintln!("Error: parsing CLI arguments failed");
-        print!("{}", args::HELP);
-        std::process::exit(1);
-    });
+/// The input path that means "read from stdin".
+pub(crate) const STDIN: &str = "-";
 
-    // day 255 is a magic day number meaning "run all days"
-    if args.day == 255 {
-        for day in 1..=25 {
-            let input = read_to_string(format!("./input/d{}", day));
-            if let Ok(input) = input {
-                run(day, 1, input.clone());
-                run(day, 2, input.clone());
-            } else {
-                print_part(day, 1, Instant::now(), "no input");
-                print_part(day, 2, Instant::now(), "no input");
-            }
-        }
-    } else if (1..=25).contains(&args.day) {
-        let input_file = if args.input.is_some() {
-            args.input.unwrap()
-        } else if args.example {
-            format!("./examples/d{}", args.day)
+/// A day to run and the input to run it with.
+struct Job {
+    day: u8,
+    /// the input file, or `-` for stdin
+    path: PathBuf,
+    /// None when the input file is missing or unreadable
+    input: Option<String>,
+}
+
+impl Job {
+    fn new(day: u8, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let input = if path.as_os_str() == STDIN {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input).ok().map(|_| input)
         } else {
-            format!("./input/d{}", args.day)
+            read_to_string(&path).ok()
         };
+        Job { day, path, input }
+    }
+}
 
-        if let Ok(input) = read_to_string(input_file) {
-            // run requested part, or run both parts if no part is requested
-            if let Some(part) = args.part {
-                run(args.day, part, input);
+/// The jobs for a day's chosen examples.  A day without any examples gets a single job for
+/// `examples/dN`, so it's reported as having no input.
+fn example_jobs(day: u8, example: Example) -> Vec<Job> {
+    match example {
+        Example::Variant(1) => vec![Job::new(day, format!("./examples/d{day}"))],
+        Example::Variant(n) => vec![Job::new(day, format!("./examples/d{day}_{n}"))],
+        Example::All => {
+            let files = example_files(day);
+            if files.is_empty() {
+                vec![Job::new(day, format!("./examples/d{day}"))]
             } else {
-                run(args.day, 1, input.clone());
-                run(args.day, 2, input);
+                files.into_iter().map(|path| Job::new(day, path)).collect()
             }
-        } else {
-            eprintln!(
-                "Error: input file for day {} is missing or unreadable",
-                args.day
-            );
         }
+    }
+}
+
+/// Every example file for a day: `dN` first, then the numbered `dN_2`, `dN_3`, ..., then any
+/// named like `dN-extra` in alphabetical order.
+fn example_files(day: u8) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir("./examples") else {
+        return vec![];
+    };
+
+    let mut files: Vec<_> = entries
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            let order = example_order(day, &name)?;
+            Some((order, name))
+        })
+        .collect();
+    files.sort();
+
+    files
+        .into_iter()
+        .map(|(_, name)| Path::new("./examples").join(name))
+        .collect()
+}
+
+/// Where an example file sorts among the day's examples, or None if it isn't one of them.
+pub(crate) fn example_order(day: u8, name: &str) -> Option<(u32, String)> {
+    let rest = name.strip_prefix(&format!("d{day}"))?;
+    if rest.is_empty() {
+        Some((1, String::new()))
+    } else if let Some(n) = rest.strip_prefix('_') {
+        Some((n.parse().ok().filter(|n| *n > 1)?, String::new()))
     } else {
-        eprintln!("Error: DAY must be 1 through 25, or 255 to run all days");
+        let extra = rest.strip_prefix('-')?;
+        Some((u32::MAX, extra.to_string()))
     }
 }
 
-fn run(day: u8, part: u8, input: String) {
-    if ![1, 2].contains(&part) {
-        eprintln!("Error: part must be 1 or 2");
+fn main() {
+    let (command, verbosity) = args::parse_args().unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        print!("{}", args::HELP);
+        std::process::exit(1);
+    });
+    logging::init(verbosity);
+
+    let args = match command {
+        Command::Run(args) => args,
+        Command::Serve(serve) => return serve::serve(&serve),
+        Command::Scaffold(scaffold) => return scaffold::scaffold(&scaffold),
+        Command::Fetch(fetch) => return fetch::fetch(&fetch),
+        Command::Submit(submit) => return submit::submit(&submit),
+    };
+
+    if (args.tick.is_some() || args.record.is_some()) && !cfg!(feature = "visualize") {
+        eprintln!(
+            "Error: --tick-ms and --record need the visualize feature, run with -F visualize or just viz"
+        );
         exit(1);
     }
 
-    let t = Instant::now();
-    match (day, part) {
-        (1, 1) => {
-            let parsed = aoc2024::d1::parse(input);
-            let output = aoc2024::d1::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (1, 2) => {
-            let parsed = aoc2024::d1::parse(input);
-            let output = aoc2024::d1::part2(parsed);
-            print_part(day, part, t, output);
-        }
-        (2, 1) => {
-            let parsed = aoc2024::d2::parse(input);
-            let output = aoc2024::d2::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (2, 2) => {
-            let parsed = aoc2024::d2::parse(input);
-            let output = aoc2024::d2::part2(parsed);
-            print_part(day, part, t, output);
-        }
-        (3, 1) => {
-            let parsed = aoc2024::d3::parse(input);
-            let output = aoc2024::d3::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (3, 2) => {
-            let parsed = aoc2024::d3::parse(input);
-            let output = aoc2024::d3::part2(parsed);
-            print_part(day, part, t, output);
-        }
-        (4, 1) => {
-            let parsed = aoc2024::d4::parse(input);
-            let output = aoc2024::d4::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (4, 2) => {
-            let parsed = aoc2024::d4::parse(input);
-            let output = aoc2024::d4::part2(parsed);
-            print_part(day, part, t, output);
-        }
-        (5, 1) => {
-            let parsed = aoc2024::d5::parse(input);
-            let output = aoc2024::d5::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (5, 2) => {
-            let parsed = aoc2024::d5::parse(input);
-            let output = aoc2024::d5::part2(parsed);
-            print_part(day, part, t, output);
-        }
-        (6, 1) => {
-            let parsed = aoc2024::d6::parse(input);
-            let output = aoc2024::d6::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (6, 2) => {
-            let parsed = aoc2024::d6::parse(input);
-            let output = aoc2024::d6::part2(parsed);
-            print_part(day, part, t, output);
-        }
-        (7, 1) => {
-            let parsed = aoc2024::d7::parse(input);
-            let output = aoc2024::d7::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (7, 2) => {
-            let parsed = aoc2024::d7::parse(input);
-            let output = aoc2024::d7::part2(parsed);
-            print_part(day, part, t, output);
-        }
-        (8, 1) => {
-            let parsed = aoc2024::d8::parse(input);
-            let output = aoc2024::d8::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (8, 2) => {
-            let parsed = aoc2024::d8::parse(input);
-            let output = aoc2024::d8::part2(parsed);
-            print_part(day, part, t, output);
-        }
-        (9, 1) => {
-            let parsed = aoc2024::d9::parse(input);
-            let output = aoc2024::d9::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (9, 2) => {
-            let parsed = aoc2024::d9::parse(input);
-            let output = aoc2024::d9::part2(parsed);
-            print_part(day, part, t, output);
-        }
-        (10, 1) => {
-            let parsed = aoc2024::d10::parse(input);
-            let output = aoc2024::d10::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (10, 2) => {
-            let parsed = aoc2024::d10::parse(input);
-            let output = aoc2024::d10::part2(parsed);
-            print_part(day, part, t, output);
-        }
-        (11, 1) => {
-            let parsed = aoc2024::d11::parse(input);
-            let output = aoc2024::d11::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (11, 2) => {
-            let parsed = aoc2024::d11::parse(input);
-            let output = aoc2024::d11::part2(parsed);
-            print_part(day, part, t, output);
-        }
-        (12, 1) => {
-            let parsed = aoc2024::d12::parse(input);
-            let output = aoc2024::d12::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (12, 2) => {
-            let parsed = aoc2024::d12::parse(input);
-            let output = aoc2024::d12::part2(parsed);
-            print_part(day, part, t, output);
-        }
-        (13, 1) => {
-            let parsed = aoc2024::d13::parse(input);
-            let output = aoc2024::d13::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (13, 2) => {
-            let parsed = aoc2024::d13::parse(input);
-            let output = aoc2024::d13::part2(parsed);
-            print_part(day, part, t, output);
-        }
-        (14, 1) => {
-            let parsed = aoc2024::d14::parse(input);
-            let output = aoc2024::d14::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (14, 2) => {
-            let parsed = aoc2024::d14::parse(input);
-            let output = aoc2024::d14::part2(parsed);
-            print_part(day, part, t, output);
-        }
-        (15, 1) => {
-            let parsed = aoc2024::d15::parse(input);
-            let output = aoc2024::d15::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (15, 2) => {
-            let parsed = aoc2024::d15::parse(input);
-            let output = aoc2024::d15::part2(parsed);
-            print_part(day, part, t, output);
-        }
-        (16, 1) => {
-            let parsed = aoc2024::d16::parse(input);
-            let output = aoc2024::d16::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (16, 2) => {
-            let parsed = aoc2024::d16::parse(input);
-            let output = aoc2024::d16::part2(parsed);
-            print_part(day, part, t, output);
-        }
-        (17, 1) => {
-            let parsed = aoc2024::d17::parse(input);
-            let output = aoc2024::d17::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (17, 2) => {
-            let parsed = aoc2024::d17::parse(input);
-            let output = aoc2024::d17::part2(parsed);
-            print_part(day, part, t, output);
-        }
-        (18, 1) => {
-            let parsed = aoc2024::d18::parse(input);
-            let output = aoc2024::d18::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (18, 2) => {
-            let parsed = aoc2024::d18::parse(input);
-            let output = aoc2024::d18::part2(parsed);
-            print_part(day, part, t, output);
-        }
-        (19, 1) => {
-            let parsed = aoc2024::d19::parse(input);
-            let output = aoc2024::d19::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (19, 2) => {
-            let parsed = aoc2024::d19::parse(input);
-            let output = aoc2024::d19::part2(parsed);
-            print_part(day, part, t, output);
-        }
-        (20, 1) => {
-            let parsed = aoc2024::d20::parse(input);
-            let output = aoc2024::d20::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (20, 2) => {
-            let parsed = aoc2024::d20::parse(input);
-            let output = aoc2024::d20::part2(parsed);
-            print_part(day, part, t, output);
-        }
-        (21, 1) => {
-            let parsed = aoc2024::d21::parse(input);
-            let output = aoc2024::d21::part1(parsed);
-            print_part(day, part, t, output);
-        }
-        (21, 2) => {
-            let parsed = aoc2024::d21::parse(input);
-            let output = aoc2024::d21::part2(parsed);
-            print_part(day, part, t, output);
+    if args.watch {
+        return watch::watch(&args);
+    }
+
+    // visualizations draw over stdout, so only when it's a terminal and nothing is being measured,
+    // but they can be recorded either way.  They aren't drawn alongside other days' output, or
+    // when a part might be abandoned still holding the terminal.
+    let draw = io::stdout().is_terminal() && args.jobs.is_none() && args.timeout.is_none();
+    if args.bench.is_none() && (draw || args.record.is_some()) {
+        visualize::enable(visualize::Settings {
+            tick: args.tick.unwrap_or(visualize::DEFAULT_TICK),
+            draw,
+            record: args.record.as_ref().map(PathBuf::from),
+        });
+    }
+
+    let parts = args.parts.0.clone();
+    let days = &args.days.0;
+
+    let jobs: Vec<Job> = if !args.input.is_empty() {
+        let [day] = days[..] else {
+            eprintln!("Error: -i/--input can only be used with a single day");
+            exit(1);
+        };
+        if args.input.iter().filter(|i| *i == STDIN).count() > 1 {
+            eprintln!("Error: stdin can only be read once");
+            exit(1);
         }
-        (22, 1) => {
-            let parsed = aoc2024::d22::parse(input);
-            let output = aoc2024::d22::part1(parsed);
-            print_part(day, part, t, output);
+
+        args.input
+            .iter()
+            .map(|input| {
+                let job = Job::new(day, input);
+                if job.input.is_none() {
+                    eprintln!("Error: input {input} is missing or unreadable");
+                    exit(1);
+                }
+                job
+            })
+            .collect()
+    } else if let Some(example) = args.example {
+        let jobs: Vec<Job> = days
+            .iter()
+            .flat_map(|&day| example_jobs(day, example))
+            .collect();
+        if let [day] = days[..] {
+            if jobs.iter().all(|job| job.input.is_none()) {
+                eprintln!("Error: example input for day {day} is missing or unreadable");
+                exit(1);
+            }
         }
-        (22, 2) => {
-            let parsed = aoc2024::d22::parse(input);
-            let output = aoc2024::d22::part2(parsed);
-            print_part(day, part, t, output);
+        jobs
+    } else if let [day] = days[..] {
+        let job = Job::new(day, format!("./input/d{}", day));
+        if job.input.is_none() {
+            eprintln!("Error: input file for day {} is missing or unreadable", day);
+            exit(1);
         }
-        (23, 1) => {
-            let parsed = aoc2024::d23::parse(input);
-            let output = aoc2024::d23::part1(parsed);
-            print_part(day, part, t, output);
+        vec![job]
+    } else {
+        // days without input are still listed, they're reported as having no input
+        days.iter()
+            .map(|&day| Job::new(day, format!("./input/d{day}")))
+            .collect()
+    };
+
+    if let Some(runs) = args.bench {
+        bench(&args, runs, &parts, jobs);
+    } else {
+        print_header(args.format);
+
+        let mut summary = Summary::default();
+        let many_days = jobs.len() > 1;
+
+        if let Some(threads) = args.jobs {
+            run_parallel(&args, &parts, jobs, threads, &mut summary);
+        } else {
+            for job in jobs {
+                print_outcome(args.format, &run(&parts, args.timeout, job), &mut summary);
+                // Ctrl-C on a visualization
+                if visualize::interrupted() {
+                    exit(130);
+                }
+            }
         }
-        (23, 2) => {
-            let parsed = aoc2024::d23::parse(input);
-            let output = aoc2024::d23::part2(parsed);
-            print_part(day, part, t, output);
+
+        if many_days {
+            print_totals(args.format, &summary);
         }
-        (24, 1) => {
-            let parsed = aoc2024::d24::parse(input);
-            let output = aoc2024::d24::part1(parsed);
-            print_part(day, part, t, output);
+
+        if summary.has_failures() {
+            exit(1);
         }
-        (24, 2) => {
-            let parsed = aoc2024::d24::parse(input);
-            let output = aoc2024::d24::part2(parsed);
-            print_part(day, part, t, output);
+    }
+}
+
+/// The records from running a job, ready to be printed.
+struct Outcome {
+    day: u8,
+    records: Vec<Record>,
+    /// None when the job had no input
+    result: Option<DayResult>,
+}
+
+/// Run the given parts of a job's day and check each answer against the stored answers.
+fn run(parts: &[u8], timeout: Option<Duration>, job: Job) -> Outcome {
+    let day = job.day;
+    let Some(input) = job.input else {
+        return Outcome {
+            day,
+            records: parts
+                .iter()
+                .map(|&p| Record::no_input(day, p, &job.path))
+                .collect(),
+            result: None,
+        };
+    };
+
+    let Some(solution) = aoc2024::get_day(day) else {
+        eprintln!("Error: day {day} is not implemented");
+        exit(1);
+    };
+
+    let answers = Answers::load(Path::new(ANSWERS_DIR), day).unwrap_or_else(|e| {
+        eprintln!("Error: {e:#}");
+        exit(1);
+    });
+
+    debug!(day, path = %job.path.display(), "running");
+    let result = solution.run_with_timeout(input, parts, timeout);
+    let records = result
+        .parts
+        .iter()
+        .map(|part| Record::solved(day, &result, part, &answers, &job.path))
+        .collect();
+
+    Outcome {
+        day,
+        records,
+        result: Some(result),
+    }
+}
+
+/// Print a job's records, tallying them in the summary.
+fn print_outcome(format: Format, outcome: &Outcome, summary: &mut Summary) {
+    for record in &outcome.records {
+        print_part(format, record);
+        summary.add(record);
+    }
+    if let Some(result) = &outcome.result {
+        print_day(format, outcome.day, result);
+    }
+}
+
+/// Run jobs concurrently on a thread pool, printing each one in order as soon as it and every job
+/// before it have finished.
+fn run_parallel(args: &Args, parts: &[u8], jobs: Vec<Job>, threads: usize, summary: &mut Summary) {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .unwrap_or_else(|e| {
+            eprintln!("Error: couldn't start thread pool: {e}");
+            exit(1);
+        });
+
+    let wall = Instant::now();
+    let (tx, rx) = mpsc::channel();
+    for (i, job) in jobs.into_iter().enumerate() {
+        let tx = tx.clone();
+        let parts = parts.to_vec();
+        let timeout = args.timeout;
+        pool.spawn(move || {
+            // the receiver only goes away if the main thread is exiting anyway
+            let _ = tx.send((i, run(&parts, timeout, job)));
+        });
+    }
+    drop(tx);
+
+    let mut pending = BTreeMap::new();
+    let mut next = 0;
+    let mut summed = Duration::ZERO;
+
+    for (i, outcome) in rx {
+        pending.insert(i, outcome);
+        while let Some(outcome) = pending.remove(&next) {
+            if let Some(result) = &outcome.result {
+                summed += result.total_time();
+            }
+            print_outcome(args.format, &outcome, summary);
+            next += 1;
         }
-        (25, 1) => {
-            let parsed = aoc2024::d25::parse(input);
-            let output = aoc2024::d25::part1(parsed);
-            print_part(day, part, t, output);
+    }
+
+    print_wall_clock(
+        args.format,
+        wall.elapsed(),
+        summed,
+        pool.current_num_threads(),
+    );
+}
+
+fn bench(args: &Args, runs: usize, parts: &[u8], jobs: Vec<Job>) {
+    if runs == 0 {
+        eprintln!("Error: --bench needs at least one run");
+        exit(1);
+    }
+
+    let baseline = match &args.baseline {
+        Some(path) => load_baseline(Path::new(path)).unwrap_or_else(|e| {
+            eprintln!("Error: couldn't read baseline {path}: {e}");
+            exit(1);
+        }),
+        None => Baseline::new(),
+    };
+    // read before benchmarking, so a file that can't be merged into doesn't waste the runs
+    let saved = args.save_baseline.as_ref().map(|path| {
+        let saved = load_baseline(Path::new(path)).unwrap_or_else(|e| {
+            eprintln!("Error: couldn't read baseline {path}: {e}");
+            exit(1);
+        });
+        (path, saved)
+    });
+
+    // measurements are keyed by day and part, so several inputs would overwrite each other
+    if jobs.windows(2).any(|pair| pair[0].day == pair[1].day) {
+        eprintln!("Error: --bench can only be used with one input per day, pick one with -e N");
+        exit(1);
+    }
+
+    bench::print_header(args.format);
+
+    let mut results = Baseline::new();
+    let mut regressed = false;
+    let mut failed = false;
+
+    // days without input are skipped, there's nothing to measure
+    for (day, input) in jobs.into_iter().filter_map(|j| Some((j.day, j.input?))) {
+        let Some(solution) = aoc2024::get_day(day) else {
+            eprintln!("Error: day {day} is not implemented");
+            exit(1);
+        };
+
+        match bench_day(solution, &input, parts, runs, &baseline) {
+            Ok(measurements) => {
+                for m in measurements {
+                    print_measurement(args.format, &m);
+                    regressed |= m.regression.is_some();
+                    results.insert(m.key, m.stats);
+                }
+            }
+            Err(failure) => {
+                eprintln!("Error: day {day} {failure}");
+                failed = true;
+            }
         }
-        (25, 2) => {
-            let parsed = aoc2024::d25::parse(input);
-            let output = aoc2024::d25::part2(parsed);
-            print_part(day, part, t, output);
+    }
+
+    if let Some((path, mut saved)) = saved {
+        // merge into the existing file so days can be saved one at a time
+        saved.extend(results);
+        if let Err(e) = save_baseline(Path::new(path), &saved) {
+            eprintln!("Error: couldn't save baseline {path}: {e}");
+            exit(1);
         }
-        _ => unimplemented!(),
+    }
+
+    if regressed || failed {
+        exit(1);
     }
 }
 
-fn print_part<T: Display>(day: u8, part: u8, time: Instant, answer: T) {
-    let t = if time.elapsed().as_nanos() > 1_000_000_000 {
-        ((time.elapsed().as_millis() as f32) / 1000.0, "s")
-    } else if time.elapsed().as_nanos() > 1_000_000 {
-        ((time.elapsed().as_micros() as f32) / 1000.0, "ms")
-    } else if time.elapsed().as_nanos() > 1_000 {
-        ((time.elapsed().as_nanos() as f32) / 1000.0, "μs")
-    } else {
-        (time.elapsed().as_nanos() as f32, "ns")
-    };
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    println!(
-        "{green}🎄{reset} {blue}d{day}p{part}{reset} {answer} {grey}({time}{time_suf})",
-        blue = color::Fg(color::Blue),
-        green = color::Fg(color::Green),
-        grey = color::Fg(color::LightBlack),
-        reset = style::Reset,
-        time = t.0,
-        time_suf = t.1,
-    );
+    #[test]
+    fn example_files_in_order() {
+        let mut names = vec![
+            "d4_10", "d4-extra", "d4_2", "d4", "d40", "d4_x", "d4_1", "d14",
+        ];
+        names.sort_by_key(|name| example_order(4, name));
+        let examples: Vec<_> = names
+            .into_iter()
+            .filter(|name| example_order(4, name).is_some())
+            .collect();
+        assert_eq!(examples, vec!["d4", "d4_2", "d4_10", "d4-extra"]);
+    }
 }
@@ -0,0 +1,206 @@
+//! Allocation counting, enabled by the `count-allocs` feature.
+//!
+//! With the feature on, a counting global allocator tracks how many allocations the process
+//! makes, how many bytes they add up to, and how many bytes are live at once.  [`measure`] uses
+//! these counters to report on a single closure, including what it allocates on other threads,
+//! like rayon's.  Since every thread's allocations count, measurements have to take turns, see
+//! [`exclusive`], and once a part is left running after a timeout nothing can be measured
+//! reliably anymore, see [`abandoned`].  With the feature off, [`measure`] just runs the closure
+//! and reports nothing, so there's no cost to the allocator.
+
+use std::sync::MutexGuard;
+
+use serde::Serialize;
+
+/// What a piece of code allocated while it ran.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct AllocStats {
+    /// number of allocations, including reallocations
+    pub allocations: u64,
+    /// total bytes requested by those allocations
+    pub bytes: u64,
+    /// the most bytes that were live at once, above what was live before it started
+    pub peak_bytes: u64,
+}
+
+/// Run `f` and count what the process allocates meanwhile.  Returns None for the stats after
+/// [`abandoned`], when something else might be allocating too.
+#[cfg(feature = "count-allocs")]
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    use std::sync::atomic::Ordering;
+
+    let start = counting::snapshot();
+    counting::reset_peak();
+    let result = f();
+    let end = counting::snapshot();
+
+    let stats = AllocStats {
+        allocations: end.allocations - start.allocations,
+        bytes: end.bytes - start.bytes,
+        peak_bytes: (end.peak - start.live).max(0) as u64,
+    };
+    let reliable = !counting::ABANDONED.load(Ordering::Relaxed);
+    (result, reliable.then_some(stats))
+}
+
+/// Run `f`; there's nothing to count without the `count-allocs` feature.
+#[cfg(not(feature = "count-allocs"))]
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    (f(), None)
+}
+
+/// Wait for any other measurement to finish, and keep others waiting until the guard is dropped,
+/// so days run with `--jobs` don't count each other's allocations.  Hold it on the thread that
+/// waits for the measurement, not on a worker that might be abandoned after a timeout and never
+/// let go.
+#[cfg(feature = "count-allocs")]
+pub fn exclusive() -> Option<MutexGuard<'static, ()>> {
+    use std::sync::Mutex;
+
+    static MEASURING: Mutex<()> = Mutex::new(());
+    // the lock guards no data, so a panic while it was held can't have left anything half done
+    Some(MEASURING.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Nothing to share without the `count-allocs` feature.
+#[cfg(not(feature = "count-allocs"))]
+pub fn exclusive() -> Option<MutexGuard<'static, ()>> {
+    None
+}
+
+/// Note that a measured closure was left running, after a timeout.  It keeps allocating on its
+/// own, so every later [`measure`] reports no stats rather than counting those too.
+#[cfg(feature = "count-allocs")]
+pub fn abandoned() {
+    use std::sync::atomic::Ordering;
+
+    if !counting::ABANDONED.swap(true, Ordering::Relaxed) {
+        tracing::warn!(
+            "a part is still running after its timeout, so allocations aren't counted from now on"
+        );
+    }
+}
+
+/// Nothing is counted without the `count-allocs` feature.
+#[cfg(not(feature = "count-allocs"))]
+pub fn abandoned() {}
+
+#[cfg(feature = "count-allocs")]
+mod counting {
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering::Relaxed},
+    };
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    /// The process's running totals.  `live` is signed so that it can start from zero whenever
+    /// it's reset, though memory allocated before then is freed later.
+    #[derive(Clone, Copy)]
+    pub(super) struct Counters {
+        pub allocations: u64,
+        pub bytes: u64,
+        pub live: i64,
+        pub peak: i64,
+    }
+
+    static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+    static BYTES: AtomicU64 = AtomicU64::new(0);
+    static LIVE: AtomicI64 = AtomicI64::new(0);
+    static PEAK: AtomicI64 = AtomicI64::new(0);
+
+    /// Set by [`super::abandoned`].
+    pub(super) static ABANDONED: AtomicBool = AtomicBool::new(false);
+
+    pub(super) fn snapshot() -> Counters {
+        Counters {
+            allocations: ALLOCATIONS.load(Relaxed),
+            bytes: BYTES.load(Relaxed),
+            live: LIVE.load(Relaxed),
+            peak: PEAK.load(Relaxed),
+        }
+    }
+
+    /// Start tracking the peak from whatever is live right now.
+    pub(super) fn reset_peak() {
+        PEAK.store(LIVE.load(Relaxed), Relaxed);
+    }
+
+    fn record(allocated: usize, freed: usize) {
+        if allocated > 0 {
+            ALLOCATIONS.fetch_add(1, Relaxed);
+            BYTES.fetch_add(allocated as u64, Relaxed);
+        }
+        let change = allocated as i64 - freed as i64;
+        let live = LIVE.fetch_add(change, Relaxed) + change;
+        PEAK.fetch_max(live, Relaxed);
+    }
+
+    /// The system allocator, counting every allocation on any thread.
+    struct CountingAllocator;
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                record(layout.size(), 0);
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                record(layout.size(), 0);
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            record(0, layout.size());
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = System.realloc(ptr, layout, new_size);
+            if !new_ptr.is_null() {
+                record(new_size, layout.size());
+            }
+            new_ptr
+        }
+    }
+}
+
+#[cfg(all(test, feature = "count-allocs"))]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    // other tests allocate on their own threads at the same time, so these only check for at
+    // least what the closure allocates
+
+    #[test]
+    fn measure_counts_allocations() {
+        let _exclusive = exclusive();
+        let (len, stats) = measure(|| {
+            let mut v: Vec<u64> = Vec::with_capacity(100);
+            v.extend(0..100);
+            let small = "0123456789".to_string();
+            v.len() + small.len()
+        });
+        assert_eq!(len, 110);
+        let stats = stats.unwrap();
+        assert!(stats.allocations >= 2);
+        assert!(stats.bytes >= 810);
+        assert!(stats.peak_bytes >= 810);
+    }
+
+    #[test]
+    fn measure_counts_other_threads() {
+        let _exclusive = exclusive();
+        let (_, stats) = measure(|| thread::spawn(|| vec![0u8; 100_000].len()).join().unwrap());
+        assert!(stats.unwrap().bytes >= 100_000);
+    }
+}
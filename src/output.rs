@@ -0,0 +1,410 @@
+use std::{path::Path, time::Duration};
+
+use aoc2024::{
+    answer::Answer,
+    answers::{input_key, Answers, Check},
+    memory::AllocStats,
+    solution::{DayResult, Failure, PartResult},
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use termion::{color, style};
+
+use crate::args::Format;
+
+/// What happened when a part was run.  Every format spells it the same way, see
+/// [`Status::as_str`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// the answer matches the stored answer
+    Correct,
+    /// the answer doesn't match the stored answer
+    Wrong,
+    /// there's no stored answer to check against
+    Unknown,
+    /// the part hasn't been solved yet
+    Incomplete,
+    /// the part, or the parse before it, returned an error
+    Error,
+    /// the part, or the parse before it, panicked
+    Panicked,
+    /// the part, or the parse before it, ran longer than the timeout
+    TimedOut,
+    /// there's no input file for the day
+    NoInput,
+}
+
+impl Status {
+    const ALL: [Status; 8] = [
+        Status::Correct,
+        Status::Wrong,
+        Status::Unknown,
+        Status::Incomplete,
+        Status::Error,
+        Status::Panicked,
+        Status::TimedOut,
+        Status::NoInput,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Correct => "correct",
+            Status::Wrong => "wrong",
+            Status::Unknown => "unknown",
+            Status::Incomplete => "incomplete",
+            Status::Error => "error",
+            Status::Panicked => "panicked",
+            Status::TimedOut => "timed_out",
+            Status::NoInput => "no_input",
+        }
+    }
+}
+
+impl Serialize for Status {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Status {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Status::ALL
+            .into_iter()
+            .find(|status| status.as_str() == s)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown status {s:?}")))
+    }
+}
+
+/// The result of running one part, in a shape that every output format can print.
+#[derive(Debug, Clone, Serialize)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    /// the input file the part was run on, or `stdin`
+    pub input: String,
+    pub answer: Option<Answer>,
+    /// the stored answer, when this one is wrong
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    /// what went wrong, when the part failed to produce an answer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub parse_ns: u64,
+    pub solve_ns: u64,
+    /// what the parse allocated, when built with the `count-allocs` feature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_alloc: Option<AllocStats>,
+    /// what the part allocated, when built with the `count-allocs` feature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub solve_alloc: Option<AllocStats>,
+    pub status: Status,
+}
+
+impl Record {
+    /// A part that was run, with its answer checked against the stored answers for the input.
+    /// The parse time and allocations are shared by every part of the day.
+    pub fn solved(
+        day: u8,
+        result: &DayResult,
+        part: &PartResult,
+        answers: &Answers,
+        input: &Path,
+    ) -> Self {
+        let (status, answer, expected, error) = match &part.answer {
+            // unsolved parts from the template are incomplete, whatever answer is stored
+            Ok(Answer::Incomplete) => (Status::Incomplete, Some(Answer::Incomplete), None, None),
+            Ok(answer) => {
+                let (status, expected) = match answers.check(input, part.part, answer) {
+                    Check::Correct => (Status::Correct, None),
+                    Check::Wrong { expected } => (Status::Wrong, Some(expected)),
+                    Check::Unknown => (Status::Unknown, None),
+                };
+                (status, Some(answer.clone()), expected, None)
+            }
+            Err(failure) => {
+                let status = match failure.cause() {
+                    Failure::Error(_) => Status::Error,
+                    Failure::TimedOut(_) => Status::TimedOut,
+                    _ => Status::Panicked,
+                };
+                (status, None, None, Some(failure.to_string()))
+            }
+        };
+
+        Record {
+            day,
+            part: part.part,
+            input: input_label(input),
+            answer,
+            expected,
+            error,
+            parse_ns: result.parse_time.as_nanos() as u64,
+            solve_ns: part.time.as_nanos() as u64,
+            parse_alloc: result.parse_alloc,
+            solve_alloc: part.alloc,
+            status,
+        }
+    }
+
+    pub fn no_input(day: u8, part: u8, input: &Path) -> Self {
+        Record {
+            day,
+            part,
+            input: input_label(input),
+            answer: None,
+            expected: None,
+            error: None,
+            parse_ns: 0,
+            solve_ns: 0,
+            parse_alloc: None,
+            solve_alloc: None,
+            status: Status::NoInput,
+        }
+    }
+
+    /// The part's short name, like `d5p1`, followed by the input when it isn't the day's usual
+    /// puzzle input, like `d5p1 [examples/d5]`.
+    pub fn name(&self) -> String {
+        if self.input == format!("input/d{}", self.day) {
+            format!("d{}p{}", self.day, self.part)
+        } else {
+            format!("d{}p{} [{}]", self.day, self.part, self.input)
+        }
+    }
+}
+
+/// How an input path is shown in the output: its answers key, or `stdin` for `-`.
+fn input_label(path: &Path) -> String {
+    if path.as_os_str() == "-" {
+        "stdin".to_string()
+    } else {
+        input_key(path)
+    }
+}
+
+/// A tally of how every part went, printed at the end of a run.
+#[derive(Debug, Default, Clone)]
+pub struct Summary {
+    /// correct, or not checked because there's no stored answer
+    pub passed: usize,
+    /// the parts that were wrong, failed, or timed out, like `d5p1` or `d5p1 [stdin]`
+    pub failed: Vec<String>,
+    pub incomplete: usize,
+    pub no_input: usize,
+}
+
+impl Summary {
+    pub fn add(&mut self, record: &Record) {
+        match record.status {
+            Status::Correct | Status::Unknown => self.passed += 1,
+            Status::Wrong | Status::Error | Status::Panicked | Status::TimedOut => {
+                self.failed.push(record.name())
+            }
+            Status::Incomplete => self.incomplete += 1,
+            Status::NoInput => self.no_input += 1,
+        }
+    }
+
+    pub fn has_failures(&self) -> bool {
+        !self.failed.is_empty()
+    }
+}
+
+/// Print anything that needs to come before the first record (currently just the TSV header).
+/// The allocation columns are empty unless built with the `count-allocs` feature.
+pub fn print_header(format: Format) {
+    if format == Format::Tsv {
+        println!(
+            "day\tpart\tinput\tstatus\tanswer\texpected\terror\tparse_ns\tsolve_ns\t\
+             parse_allocs\tparse_bytes\tparse_peak_bytes\tsolve_allocs\tsolve_bytes\tsolve_peak_bytes"
+        );
+    }
+}
+
+pub fn print_part(format: Format, record: &Record) {
+    match format {
+        Format::Pretty => print_pretty(record),
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string(record).expect("records are always serializable")
+        ),
+        Format::Tsv => println!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            record.day,
+            record.part,
+            tsv_escape(Some(&record.input)),
+            record.status.as_str(),
+            tsv_escape(record.answer.as_ref().map(|a| a.to_string()).as_deref()),
+            tsv_escape(record.expected.as_deref()),
+            tsv_escape(record.error.as_deref()),
+            record.parse_ns,
+            record.solve_ns,
+            tsv_alloc(record.parse_alloc),
+            tsv_alloc(record.solve_alloc),
+        ),
+    }
+}
+
+/// Tabs and newlines would break a TSV row, so escape them.
+fn tsv_escape(value: Option<&str>) -> String {
+    value
+        .unwrap_or("")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+/// The three allocation columns, or three empty columns when allocations weren't counted.
+fn tsv_alloc(alloc: Option<AllocStats>) -> String {
+    match alloc {
+        Some(a) => format!("{}\t{}\t{}", a.allocations, a.bytes, a.peak_bytes),
+        None => "\t\t".to_string(),
+    }
+}
+
+/// Print the parse time and total time for a day, after its parts.  Only the pretty format
+/// prints this, the other formats carry the parse time on every record instead.
+pub fn print_day(format: Format, day: u8, result: &DayResult) {
+    if format == Format::Pretty {
+        println!(
+            "{grey}   d{day} parse {parse}{alloc} · total {total}{reset}",
+            grey = color::Fg(color::LightBlack),
+            reset = style::Reset,
+            parse = fmt_duration(result.parse_time),
+            alloc = fmt_alloc(result.parse_alloc),
+            total = fmt_duration(result.total_time()),
+        );
+    }
+}
+
+/// Print how many parts passed, failed, or are incomplete.  Only the pretty format prints this.
+pub fn print_totals(format: Format, summary: &Summary) {
+    if format != Format::Pretty {
+        return;
+    }
+
+    let failed = if summary.has_failures() {
+        format!(
+            "{red}{} failed ({}){grey}",
+            summary.failed.len(),
+            summary.failed.join(", "),
+            red = color::Fg(color::Red),
+            grey = color::Fg(color::LightBlack),
+        )
+    } else {
+        "0 failed".to_string()
+    };
+
+    println!(
+        "{grey}   {passed} passed · {failed} · {incomplete} incomplete · {no_input} without input{reset}",
+        grey = color::Fg(color::LightBlack),
+        reset = style::Reset,
+        passed = summary.passed,
+        incomplete = summary.incomplete,
+        no_input = summary.no_input,
+    );
+}
+
+/// Print how long a parallel run took compared to the sum of every day's time.  Only the pretty
+/// format prints this.
+pub fn print_wall_clock(format: Format, wall: Duration, summed: Duration, threads: usize) {
+    if format == Format::Pretty {
+        println!(
+            "{grey}   wall clock {wall} · summed {summed} · {speedup:.1}x on {threads} threads{reset}",
+            grey = color::Fg(color::LightBlack),
+            reset = style::Reset,
+            speedup = summed.as_secs_f64() / wall.as_secs_f64(),
+            wall = fmt_duration(wall),
+            summed = fmt_duration(summed),
+        );
+    }
+}
+
+fn print_pretty(record: &Record) {
+    let answer = match (&record.answer, &record.error) {
+        // start grids on their own line so their rows line up
+        (Some(Answer::Grid(grid)), _) => format!("\n{grid}\n"),
+        (Some(answer), _) => answer.to_string(),
+        (None, Some(error)) if record.status == Status::TimedOut => {
+            format!("{}⏱ {error}", color::Fg(color::Yellow))
+        }
+        (None, Some(error)) if record.status == Status::Error => {
+            format!("{}⚠ {error}", color::Fg(color::Red))
+        }
+        (None, Some(error)) => format!("{}💥 {error}", color::Fg(color::Red)),
+        (None, None) => record.status.as_str().to_string(),
+    };
+    let time = fmt_duration(Duration::from_nanos(record.solve_ns));
+    let check = match (record.status, &record.expected) {
+        (Status::Correct, _) => format!(" {}✓", color::Fg(color::Green)),
+        (Status::Wrong, Some(expected)) => {
+            format!(" {}✗ expected {expected}", color::Fg(color::Red))
+        }
+        (Status::Unknown, _) => format!(" {}?", color::Fg(color::LightBlack)),
+        _ => String::new(),
+    };
+
+    println!(
+        "{green}🎄{reset} {blue}{name}{reset} {answer}{check}{reset} {grey}({time}{alloc})",
+        blue = color::Fg(color::Blue),
+        green = color::Fg(color::Green),
+        grey = color::Fg(color::LightBlack),
+        reset = style::Reset,
+        name = record.name(),
+        alloc = fmt_alloc(record.solve_alloc),
+    );
+}
+
+/// Format allocation counts to follow a duration, or nothing when allocations weren't counted.
+fn fmt_alloc(alloc: Option<AllocStats>) -> String {
+    match alloc {
+        Some(a) => format!(
+            ", {} allocs · {} · peak {}",
+            a.allocations,
+            fmt_bytes(a.bytes),
+            fmt_bytes(a.peak_bytes)
+        ),
+        None => String::new(),
+    }
+}
+
+/// Format a byte count with the most readable unit.
+fn fmt_bytes(bytes: u64) -> String {
+    if bytes >= 1 << 30 {
+        format!("{:.1}GiB", bytes as f64 / (1u64 << 30) as f64)
+    } else if bytes >= 1 << 20 {
+        format!("{:.1}MiB", bytes as f64 / (1u64 << 20) as f64)
+    } else if bytes >= 1 << 10 {
+        format!("{:.1}KiB", bytes as f64 / (1u64 << 10) as f64)
+    } else {
+        format!("{bytes}B")
+    }
+}
+
+/// Format a duration with the most readable unit.
+pub fn fmt_duration(time: Duration) -> String {
+    if time.as_nanos() > 1_000_000_000 {
+        format!("{}s", (time.as_millis() as f32) / 1000.0)
+    } else if time.as_nanos() > 1_000_000 {
+        format!("{}ms", (time.as_micros() as f32) / 1000.0)
+    } else if time.as_nanos() > 1_000 {
+        format!("{}μs", (time.as_nanos() as f32) / 1000.0)
+    } else {
+        format!("{}ns", time.as_nanos())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_is_spelled_the_same_everywhere() {
+        for status in Status::ALL {
+            let json = serde_json::to_string(&status).unwrap();
+            assert_eq!(json, format!("\"{}\"", status.as_str()));
+            assert_eq!(serde_json::from_str::<Status>(&json).unwrap(), status);
+        }
+        assert_eq!(Status::TimedOut.as_str(), "timed_out");
+        assert!(serde_json::from_str::<Status>("\"timed out\"").is_err());
+    }
+}
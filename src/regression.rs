@@ -0,0 +1,19 @@
+//! Regression tests generated by `build.rs` from every answer stored in `answers/`, so a day is
+//! tested as soon as its answers are known.  Run one day's with `just test 7`.
+
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+
+/// Solve a part for an input file and compare it to the stored answer.
+fn check(day: u8, part: u8, input: &str, expected: &str) -> Result<()> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(input);
+    let input = fs::read_to_string(&path).with_context(|| format!("reading {input}"))?;
+    let solved = crate::solve(day, part, &input)?;
+    if !solved.answer.matches(expected) {
+        bail!("expected {expected}, got {}", solved.answer);
+    }
+    Ok(())
+}
+
+include!(concat!(env!("OUT_DIR"), "/regression_tests.rs"));
@@ -0,0 +1,180 @@
+//! `aoc2024 scaffold`, which starts a new day: its source from the template, an empty example
+//! file, and the module registered in `src/lib.rs`.
+
+use std::{
+    fs::{self, OpenOptions},
+    io::ErrorKind,
+    path::Path,
+    process::exit,
+};
+
+use anyhow::{bail, Context, Result};
+
+use crate::args::ScaffoldArgs;
+
+const TEMPLATE: &str = "templates/d.rs";
+const LIB: &str = "src/lib.rs";
+
+pub fn scaffold(args: &ScaffoldArgs) {
+    if let Err(e) = try_scaffold(args) {
+        eprintln!("Error: {e:#}");
+        exit(1);
+    }
+}
+
+fn try_scaffold(args: &ScaffoldArgs) -> Result<()> {
+    let day = args.day;
+    println!("starting day {day} year {}", args.year);
+
+    let source = format!("src/d{day}.rs");
+    if Path::new(&source).exists() && !args.force {
+        bail!("{source} already exists, use --force to overwrite it");
+    }
+    let template = fs::read_to_string(TEMPLATE).with_context(|| format!("reading {TEMPLATE}"))?;
+    fs::write(&source, render(&template, day, args.year))
+        .with_context(|| format!("writing {source}"))?;
+    println!("source created: {source}");
+
+    // an example may already have been pasted in, so never truncate it
+    let example = format!("examples/d{day}");
+    fs::create_dir_all("examples").context("creating examples/")?;
+    match OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&example)
+    {
+        Ok(_) => println!("empty example created: {example}"),
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+            println!("example for day {day} already exists in {example}")
+        }
+        Err(e) => return Err(e).with_context(|| format!("creating {example}")),
+    }
+
+    let lib = fs::read_to_string(LIB).with_context(|| format!("reading {LIB}"))?;
+    let registered = register(&lib, day)?;
+    if registered == lib {
+        println!("day {day} is already registered in {LIB}");
+    } else {
+        fs::write(LIB, registered).with_context(|| format!("writing {LIB}"))?;
+        println!("day {day} registered in {LIB}");
+    }
+
+    Ok(())
+}
+
+/// Fill in the template's `$AOC_DAY` and `$AOC_YEAR`, written either bare or as `${...}`.
+fn render(template: &str, day: u8, year: u16) -> String {
+    let (day, year) = (day.to_string(), year.to_string());
+    template
+        .replace("${AOC_DAY}", &day)
+        .replace("$AOC_DAY", &day)
+        .replace("${AOC_YEAR}", &year)
+        .replace("$AOC_YEAR", &year)
+}
+
+/// Add `pub mod dN;` and `&dN::Solver` to lib.rs, each in order among the other days, unless
+/// they're there already.  The runner finds every day through `DAYS`, so that's all it takes.
+fn register(lib: &str, day: u8) -> Result<String> {
+    let mut lines: Vec<String> = lib.lines().map(str::to_string).collect();
+
+    let module = format!("pub mod d{day};");
+    if !lines.contains(&module) {
+        // rustfmt sorts modules by name, so d10 comes before d2
+        let Some(last) = lines.iter().rposition(|l| l.starts_with("pub mod ")) else {
+            bail!("{LIB} has no modules to add d{day} to");
+        };
+        let name = format!("d{day}");
+        let at = lines
+            .iter()
+            .position(|l| module_name(l).is_some_and(|m| m > name.as_str()))
+            .unwrap_or(last + 1);
+        lines.insert(at, module);
+    }
+
+    let solver = format!("    &d{day}::Solver,");
+    if !lines.contains(&solver) {
+        let Some(start) = lines.iter().position(|l| l.starts_with("pub static DAYS")) else {
+            bail!("{LIB} has no DAYS to register d{day} in");
+        };
+        let Some(end) = lines[start..].iter().position(|l| l == "];") else {
+            bail!("{LIB} has no end to DAYS");
+        };
+        let end = start + end;
+        // DAYS is in day order
+        let at = (start + 1..end)
+            .find(|&i| solver_day(&lines[i]).is_some_and(|d| d > day))
+            .unwrap_or(end);
+        lines.insert(at, solver);
+    }
+
+    let mut registered = lines.join("\n");
+    if lib.ends_with('\n') {
+        registered.push('\n');
+    }
+    Ok(registered)
+}
+
+/// The module in a line like `pub mod d7;`.
+fn module_name(line: &str) -> Option<&str> {
+    line.strip_prefix("pub mod ")?.strip_suffix(';')
+}
+
+/// The day in a `DAYS` line like `    &d7::Solver,`.
+fn solver_day(line: &str) -> Option<u8> {
+    line.trim()
+        .strip_prefix("&d")?
+        .strip_suffix("::Solver,")?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = "\
+pub mod answer;
+pub mod d1;
+pub mod d3;
+pub mod grid;
+
+pub static DAYS: &[&dyn Day] = &[
+    &d1::Solver,
+    &d3::Solver,
+];
+";
+
+    #[test]
+    fn renders_template() {
+        assert_eq!(
+            render("day $AOC_DAY/$AOC_YEAR fn d${AOC_DAY}p1", 12, 2024),
+            "day 12/2024 fn d12p1"
+        );
+    }
+
+    #[test]
+    fn registers_days_in_order() {
+        let registered = register(LIB, 2).unwrap();
+        assert_eq!(
+            registered,
+            "\
+pub mod answer;
+pub mod d1;
+pub mod d2;
+pub mod d3;
+pub mod grid;
+
+pub static DAYS: &[&dyn Day] = &[
+    &d1::Solver,
+    &d2::Solver,
+    &d3::Solver,
+];
+"
+        );
+        assert_eq!(register(&registered, 2).unwrap(), registered);
+
+        let registered = register(LIB, 10).unwrap();
+        assert!(registered.contains("pub mod d1;\npub mod d10;\npub mod d3;"));
+        assert!(registered.contains("    &d3::Solver,\n    &d10::Solver,\n];"));
+    }
+}
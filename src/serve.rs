@@ -0,0 +1,238 @@
+//! `aoc2024 serve`, a small local HTTP server for solving inputs from a browser or a script.
+//!
+//! - `GET /` a page to paste an input into
+//! - `GET /days` every registered day, as JSON
+//! - `POST /days/{day}/{part}` solve a part for the input in the request body, answering with
+//!   the answer and timings as JSON
+//!
+//! It only speaks enough HTTP/1.1 for that, one request per connection.
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    process::exit,
+    thread,
+    time::Duration,
+};
+
+use aoc2024::answer::Answer;
+use serde::Serialize;
+
+use crate::args::ServeArgs;
+
+/// Puzzle inputs are tens of kilobytes, anything much bigger is a mistake.
+const MAX_BODY: usize = 10 * 1024 * 1024;
+
+/// How long a connection can sit idle before it's dropped.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+const INDEX: &str = include_str!("serve.html");
+
+/// Listen until the process is killed, handling each connection on its own thread.  The address
+/// is printed once the server is listening, which is how a caller finds the port when asking
+/// for any free one with `--port 0`.
+pub fn serve(args: &ServeArgs) {
+    let listener = TcpListener::bind((args.host.as_str(), args.port)).unwrap_or_else(|e| {
+        eprintln!("Error: couldn't listen on {}:{}: {e}", args.host, args.port);
+        exit(1);
+    });
+    let addr = listener
+        .local_addr()
+        .expect("a bound listener has an address");
+    println!("listening on http://{addr}");
+    // the address has to get out even when stdout is a pipe
+    let _ = io::stdout().flush();
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                thread::spawn(move || handle(stream));
+            }
+            Err(e) => eprintln!("Error: couldn't accept a connection: {e}"),
+        }
+    }
+}
+
+fn handle(mut stream: TcpStream) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let response = match read_request(BufReader::new(&stream)) {
+        Ok(request) => route(&request),
+        Err(response) => response,
+    };
+    // the client may have gone away already, there's nobody to tell
+    let _ = stream.write_all(&response.to_bytes());
+}
+
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+#[derive(Debug)]
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn json(status: u16, body: &impl Serialize) -> Self {
+        Response {
+            status,
+            content_type: "application/json",
+            body: serde_json::to_string(body).expect("responses are always serializable"),
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        #[derive(Serialize)]
+        struct Error {
+            error: String,
+        }
+        Response::json(
+            status,
+            &Error {
+                error: message.into(),
+            },
+        )
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            413 => "Payload Too Large",
+            422 => "Unprocessable Entity",
+            _ => "",
+        };
+        format!(
+            "HTTP/1.1 {} {reason}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            self.content_type,
+            self.body.len(),
+            self.body
+        )
+        .into_bytes()
+    }
+}
+
+/// Read the request line, the headers (only `Content-Length` matters), and the body.
+fn read_request(mut reader: impl BufRead) -> Result<Request, Response> {
+    let bad_request = |e: io::Error| Response::error(400, format!("couldn't read request: {e}"));
+
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(bad_request)?;
+    let mut request_line = line.split_whitespace();
+    let (Some(method), Some(target)) = (request_line.next(), request_line.next()) else {
+        return Err(Response::error(400, "malformed request line"));
+    };
+    let method = method.to_string();
+    // the query string isn't used for anything
+    let path = target.split('?').next().unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).map_err(bad_request)? == 0 {
+            break;
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| Response::error(400, "invalid Content-Length"))?;
+            }
+        }
+    }
+
+    if content_length > MAX_BODY {
+        return Err(Response::error(
+            413,
+            format!("inputs can be at most {MAX_BODY} bytes"),
+        ));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).map_err(bad_request)?;
+
+    Ok(Request { method, path, body })
+}
+
+fn route(request: &Request) -> Response {
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+
+    match (request.method.as_str(), &segments[..]) {
+        ("GET", [""]) => Response {
+            status: 200,
+            content_type: "text/html; charset=utf-8",
+            body: INDEX.to_string(),
+        },
+        ("GET", ["days"]) => list_days(),
+        ("POST", ["days", day, part]) => solve(day, part, &request.body),
+        (_, [""] | ["days"] | ["days", _, _]) => Response::error(405, "method not allowed"),
+        _ => Response::error(404, format!("no such endpoint: {}", request.path)),
+    }
+}
+
+fn list_days() -> Response {
+    #[derive(Serialize)]
+    struct DayInfo {
+        day: u8,
+        title: &'static str,
+    }
+
+    let days: Vec<DayInfo> = aoc2024::DAYS
+        .iter()
+        .map(|d| DayInfo {
+            day: d.day(),
+            title: d.title(),
+        })
+        .collect();
+    Response::json(200, &days)
+}
+
+fn solve(day: &str, part: &str, body: &[u8]) -> Response {
+    #[derive(Serialize)]
+    struct Solved {
+        day: u8,
+        part: u8,
+        answer: Answer,
+        parse_ns: u64,
+        solve_ns: u64,
+    }
+
+    let Some(day) = day
+        .parse()
+        .ok()
+        .filter(|day| aoc2024::get_day(*day).is_some())
+    else {
+        return Response::error(404, format!("no such day: {day}"));
+    };
+    let Some(part) = part.parse().ok().filter(|part| [1, 2].contains(part)) else {
+        return Response::error(404, format!("no such part: {part}"));
+    };
+    let Ok(input) = std::str::from_utf8(body) else {
+        return Response::error(400, "the input must be UTF-8 text");
+    };
+
+    match aoc2024::solve(day, part, input) {
+        Ok(solved) => Response::json(
+            200,
+            &Solved {
+                day,
+                part,
+                answer: solved.answer,
+                parse_ns: solved.parse_time.as_nanos() as u64,
+                solve_ns: solved.solve_time.as_nanos() as u64,
+            },
+        ),
+        Err(e) => Response::error(422, format!("{e:#}")),
+    }
+}
@@ -0,0 +1,176 @@
+//! Talking to the Advent of Code site, for `fetch` and `submit`.
+//!
+//! Requests are spaced out by [`MIN_INTERVAL`] and carry a [`USER_AGENT`] pointing back at this
+//! repo, as the site asks.
+
+use std::{
+    fs,
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Context, Result};
+use tracing::debug;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+const USER_AGENT: &str = concat!(
+    "aoc2024/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/Jared-Sprague/advent-of-code-2024)"
+);
+
+/// The least time between two requests to the site.
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Where downloaded puzzle pages and submitted guesses are kept.
+pub const CACHE_DIR: &str = ".cache";
+
+/// Something that can make requests.  The site is reached through [`Ureq`] normally, this is
+/// here so that it can be swapped for anything else.
+pub trait Http {
+    /// The body of a successful response to a GET of `url`, sent with the session cookie.
+    fn get(&self, url: &str, session: &str) -> Result<String>;
+
+    /// The body of a successful response to a form POST to `url`, sent with the session cookie.
+    fn post(&self, url: &str, session: &str, form: &[(&str, &str)]) -> Result<String>;
+}
+
+/// The usual [`Http`] backend.
+pub struct Ureq(ureq::Agent);
+
+impl Ureq {
+    pub fn new() -> Self {
+        Ureq(
+            ureq::AgentBuilder::new()
+                .user_agent(USER_AGENT)
+                .timeout(Duration::from_secs(30))
+                .build(),
+        )
+    }
+}
+
+impl Http for Ureq {
+    fn get(&self, url: &str, session: &str) -> Result<String> {
+        let response = self
+            .0
+            .get(url)
+            .set("Cookie", &format!("session={session}"))
+            .call()
+            .map_err(|e| status_error(url, e))?;
+        response
+            .into_string()
+            .with_context(|| format!("reading {url}"))
+    }
+
+    fn post(&self, url: &str, session: &str, form: &[(&str, &str)]) -> Result<String> {
+        let response = self
+            .0
+            .post(url)
+            .set("Cookie", &format!("session={session}"))
+            .send_form(form)
+            .map_err(|e| status_error(url, e))?;
+        response
+            .into_string()
+            .with_context(|| format!("reading {url}"))
+    }
+}
+
+/// Explain the error statuses the site is known to answer with.
+fn status_error(url: &str, e: ureq::Error) -> anyhow::Error {
+    match e {
+        ureq::Error::Status(404, _) => {
+            anyhow!("{url} was not found, has the puzzle been unlocked yet?")
+        }
+        ureq::Error::Status(400 | 500, _) => {
+            anyhow!("{url} was refused, has AOC_SESSION expired?")
+        }
+        e => anyhow::Error::new(e).context(url.to_string()),
+    }
+}
+
+/// One year of the site, reached through an [`Http`] backend, with requests spaced out.
+pub struct Site<H> {
+    http: H,
+    base_url: String,
+    pub year: u16,
+    session: String,
+    last_request: Option<Instant>,
+}
+
+impl<H: Http> Site<H> {
+    pub fn new(http: H, base_url: &str, year: u16, session: String) -> Self {
+        Site {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            year,
+            session,
+            last_request: None,
+        }
+    }
+
+    fn wait_turn(&mut self) {
+        if let Some(last) = self.last_request {
+            thread::sleep(MIN_INTERVAL.saturating_sub(last.elapsed()));
+        }
+        self.last_request = Some(Instant::now());
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}/{path}", self.base_url, self.year)
+    }
+
+    /// GET a path under the year, like `day/3/input`.
+    pub fn get(&mut self, path: &str) -> Result<String> {
+        self.wait_turn();
+        debug!(url = self.url(path), "GET");
+        self.http.get(&self.url(path), &self.session)
+    }
+
+    /// POST a form to a path under the year, like `day/3/answer`.
+    pub fn post(&mut self, path: &str, form: &[(&str, &str)]) -> Result<String> {
+        self.wait_turn();
+        debug!(url = self.url(path), "POST");
+        self.http.post(&self.url(path), &self.session, form)
+    }
+}
+
+/// The session cookie from `AOC_SESSION`, which every request needs.
+pub fn session() -> Result<String> {
+    std::env::var("AOC_SESSION")
+        .map_err(|_| anyhow!("please set AOC_SESSION to your adventofcode.com session cookie"))
+}
+
+/// Write a file, creating its directory if needed.
+pub fn write(path: &Path, contents: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    }
+    fs::write(path, contents).with_context(|| format!("writing {}", path.display()))
+}
+
+/// Drop tags, like the `<em>` used to highlight parts of an example.
+pub fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Replace the few HTML entities that show up in examples.  `&amp;` goes last so that `&amp;lt;`
+/// becomes `&lt;` and not `<`.
+pub fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
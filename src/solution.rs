@@ -0,0 +1,478 @@
+use std::{
+    any::Any,
+    cell::Cell,
+    fmt::Display,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Once,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use tracing::debug;
+
+use crate::{
+    answer::Answer,
+    memory::{self, AllocStats},
+};
+
+/// A solution to one day's puzzle.  Each `dN` module implements this on its `Solver` type and
+/// registers it in [`crate::DAYS`].
+pub trait Solution {
+    /// The day number, 1 through 25.
+    const DAY: u8;
+    /// The puzzle's title, as shown on adventofcode.com.
+    const TITLE: &'static str;
+
+    /// Cloned so that both parts can be solved from a single parse of the input, and sent to a
+    /// worker thread when running with a timeout.
+    type Model: Clone + Send + 'static;
+
+    /// Parse the puzzle input.  Invalid input is an error, ideally with context saying where in
+    /// the input the problem is (see [`crate::input`]).
+    fn parse(input: String) -> anyhow::Result<Self::Model>;
+    fn part1(model: Self::Model) -> anyhow::Result<Answer>;
+    fn part2(model: Self::Model) -> anyhow::Result<Answer>;
+}
+
+/// An object-safe view of a [`Solution`], so days with different `Model` and `Answer` types can
+/// live side by side in the registry.
+pub trait Day: Sync {
+    fn day(&self) -> u8;
+    fn title(&self) -> &'static str;
+
+    /// Parse the input once and solve each of the given parts, timing the parse and each part
+    /// separately.  An error or a panic in the parse or a part is reported as that part's
+    /// [`Failure`] rather than going any further.
+    ///
+    /// Panics if any part is not 1 or 2.
+    fn run(&self, input: String, parts: &[u8]) -> DayResult {
+        self.run_with_timeout(input, parts, None)
+    }
+
+    /// Like [`Day::run`], but when a timeout is given the parse and each part run on a worker
+    /// thread, and any that take longer than the timeout are reported as timed out.  The worker
+    /// can't be stopped, so it's abandoned and keeps running in the background until it finishes
+    /// or the process exits.
+    fn run_with_timeout(&self, input: String, parts: &[u8], timeout: Option<Duration>)
+        -> DayResult;
+
+    /// Just parse the input, keeping the model to solve parts with one at a time, like the
+    /// benchmarks do.  Nothing is timed or caught.
+    fn parse_model(&self, input: String) -> anyhow::Result<Box<dyn Parsed>>;
+}
+
+/// A day's parsed model, whatever its type, from [`Day::parse_model`].
+pub trait Parsed: Send {
+    /// A copy of the model, so a part can be solved more than once.
+    fn copy(&self) -> Box<dyn Parsed>;
+
+    /// Solve a part with the model.
+    ///
+    /// Panics if the part is not 1 or 2.
+    fn solve(self: Box<Self>, part: u8) -> anyhow::Result<Answer>;
+}
+
+struct Model<S: Solution>(S::Model);
+
+impl<S: Solution + 'static> Parsed for Model<S> {
+    fn copy(&self) -> Box<dyn Parsed> {
+        Box::new(Model::<S>(self.0.clone()))
+    }
+
+    fn solve(self: Box<Self>, part: u8) -> anyhow::Result<Answer> {
+        match part {
+            1 => S::part1(self.0),
+            2 => S::part2(self.0),
+            _ => panic!("part must be 1 or 2"),
+        }
+    }
+}
+
+/// The results of running one or more parts of a day against a single parse of the input.
+#[derive(Debug, Clone)]
+pub struct DayResult {
+    pub parse_time: Duration,
+    /// what the parse allocated, when built with the `count-allocs` feature
+    pub parse_alloc: Option<AllocStats>,
+    pub parts: Vec<PartResult>,
+}
+
+impl DayResult {
+    /// Parse time plus the time taken by every part.
+    pub fn total_time(&self) -> Duration {
+        self.parse_time + self.parts.iter().map(|p| p.time).sum::<Duration>()
+    }
+}
+
+/// The answer to one part and how long solving it took (not including the parse).
+#[derive(Debug, Clone)]
+pub struct PartResult {
+    pub part: u8,
+    pub answer: Result<Answer, Failure>,
+    pub time: Duration,
+    /// what solving allocated, when built with the `count-allocs` feature
+    pub alloc: Option<AllocStats>,
+}
+
+/// Why a part didn't produce an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    /// the part returned an error, formatted with its context
+    Error(String),
+    /// the part panicked with this message
+    Panicked(String),
+    /// the part was still running when the timeout ran out
+    TimedOut(Duration),
+    /// parsing the input failed, so the part never ran
+    Parse(Box<Failure>),
+}
+
+impl Failure {
+    /// The underlying failure, looking through a failed parse.
+    pub fn cause(&self) -> &Failure {
+        match self {
+            Failure::Parse(cause) => cause.cause(),
+            failure => failure,
+        }
+    }
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Failure::Error(msg) => write!(f, "error: {msg}"),
+            Failure::Panicked(msg) => write!(f, "panicked: {msg}"),
+            Failure::TimedOut(timeout) => write!(f, "timed out after {timeout:?}"),
+            Failure::Parse(cause) => write!(f, "parse {cause}"),
+        }
+    }
+}
+
+impl std::error::Error for Failure {}
+
+/// Worker threads get the same stack size as the main thread, so recursive solutions behave the
+/// same with and without a timeout.
+const WORKER_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// How long something took and what it allocated.
+type Cost = (Duration, Option<AllocStats>);
+
+/// Run, time, and count the allocations of `f`, catching any panic.  With a timeout, `f` runs on
+/// a worker thread that's abandoned if it doesn't finish in time.  It keeps running, and
+/// allocating, so no allocations are reported after that, see [`memory::abandoned`].
+fn guarded<T: Send + 'static>(
+    timeout: Option<Duration>,
+    f: impl FnOnce() -> T + Send + 'static,
+) -> (Result<T, Failure>, Cost) {
+    // taken here rather than on the worker, which might be abandoned still holding it
+    let _exclusive = memory::exclusive();
+    let timed = move || {
+        let t = Instant::now();
+        let (result, alloc) = memory::measure(|| catch_panic(f).map_err(Failure::Panicked));
+        (result, (t.elapsed(), alloc))
+    };
+
+    let Some(timeout) = timeout else {
+        return timed();
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let spawned = thread::Builder::new()
+        .stack_size(WORKER_STACK_SIZE)
+        .spawn(move || {
+            // nobody is listening anymore if the timeout already ran out
+            let _ = tx.send(timed());
+        });
+
+    if let Err(e) = spawned {
+        return (
+            Err(Failure::Panicked(format!(
+                "couldn't start worker thread: {e}"
+            ))),
+            (Duration::ZERO, None),
+        );
+    }
+
+    match rx.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => {
+            memory::abandoned();
+            (Err(Failure::TimedOut(timeout)), (timeout, None))
+        }
+        Err(RecvTimeoutError::Disconnected) => (
+            Err(Failure::Panicked("worker thread exited early".to_string())),
+            (Duration::ZERO, None),
+        ),
+    }
+}
+
+thread_local! {
+    /// Whether this thread is inside [`catch_panic`].
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+/// How many [`catch_panic`] calls are running on any thread.
+static SOLVING: AtomicUsize = AtomicUsize::new(0);
+
+/// Run `f`, catching a panic and returning its message instead.  The message ends up in the
+/// [`Failure`], so the default hook's report to stderr is replaced with a debug event.
+fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    install_quiet_hook();
+    let was_quiet = QUIET.replace(true);
+    SOLVING.fetch_add(1, Ordering::SeqCst);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    SOLVING.fetch_sub(1, Ordering::SeqCst);
+    QUIET.set(was_quiet);
+    result.map_err(|payload| panic_message(&*payload))
+}
+
+/// Wrap the panic hook once so that panics [`catch_panic`] will catch don't print.  That covers
+/// the thread it runs on and, while anything is being solved, rayon's workers, whose panics are
+/// passed back to the solution that spawned the work.  Every other panic goes to the old hook.
+fn install_quiet_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let caught = QUIET.get()
+                || (SOLVING.load(Ordering::SeqCst) > 0 && rayon::current_thread_index().is_some());
+            if caught {
+                debug!("{info}");
+            } else {
+                previous(info);
+            }
+        }));
+    });
+}
+
+/// Treat an error returned by a solution the same way as a panic, as a [`Failure`].
+fn flatten<T>(result: Result<anyhow::Result<T>, Failure>) -> Result<T, Failure> {
+    result.and_then(|r| r.map_err(|e| Failure::Error(format!("{e:#}"))))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+impl<S: Solution + Sync + 'static> Day for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        S::TITLE
+    }
+
+    fn run_with_timeout(
+        &self,
+        input: String,
+        parts: &[u8],
+        timeout: Option<Duration>,
+    ) -> DayResult {
+        assert!(
+            parts.iter().all(|part| [1, 2].contains(part)),
+            "part must be 1 or 2"
+        );
+
+        let (model, (parse_time, parse_alloc)) = guarded(timeout, move || S::parse(input));
+        let model = flatten(model);
+
+        let parts = parts
+            .iter()
+            .map(|&part| {
+                // clone outside the timed section so only the solve is measured
+                let model = match &model {
+                    Ok(model) => model.clone(),
+                    Err(failure) => {
+                        return PartResult {
+                            part,
+                            answer: Err(Failure::Parse(Box::new(failure.clone()))),
+                            time: Duration::ZERO,
+                            alloc: None,
+                        }
+                    }
+                };
+
+                let (answer, (time, alloc)) = guarded(timeout, move || match part {
+                    1 => S::part1(model),
+                    _ => S::part2(model),
+                });
+                let answer = flatten(answer);
+
+                PartResult {
+                    part,
+                    answer,
+                    time,
+                    alloc,
+                }
+            })
+            .collect();
+
+        DayResult {
+            parse_time,
+            parse_alloc,
+            parts,
+        }
+    }
+
+    fn parse_model(&self, input: String) -> anyhow::Result<Box<dyn Parsed>> {
+        Ok(Box::new(Model::<S>(S::parse(input)?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_ordered_by_day() {
+        let days: Vec<u8> = crate::DAYS.iter().map(|d| d.day()).collect();
+        assert_eq!(days, (1..=25).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn run_parses_once_for_both_parts() {
+        let example = include_str!("../examples/d7").to_string();
+        let result = crate::get_day(7).unwrap().run(example, &[1, 2]);
+        let answers: Vec<(u8, Answer)> = result
+            .parts
+            .into_iter()
+            .map(|p| (p.part, p.answer.unwrap()))
+            .collect();
+        assert_eq!(
+            answers,
+            vec![(1, Answer::Int(3749)), (2, Answer::Int(11387))]
+        );
+    }
+
+    #[test]
+    fn parsed_model_solves_each_part() {
+        let example = include_str!("../examples/d7").to_string();
+        let model = crate::get_day(7).unwrap().parse_model(example).unwrap();
+        assert_eq!(model.copy().solve(1).unwrap(), Answer::Int(3749));
+        assert_eq!(model.copy().solve(2).unwrap(), Answer::Int(11387));
+        assert_eq!(model.solve(1).unwrap(), Answer::Int(3749));
+    }
+
+    #[derive(Clone)]
+    struct Panicky;
+
+    impl Solution for Panicky {
+        const DAY: u8 = 1;
+        const TITLE: &'static str = "Panicky";
+
+        type Model = bool;
+
+        fn parse(input: String) -> anyhow::Result<bool> {
+            match input.as_str() {
+                "bad" => panic!("bad input"),
+                "invalid" => anyhow::bail!("line 1: invalid input"),
+                _ => Ok(true),
+            }
+        }
+
+        fn part1(_: bool) -> anyhow::Result<Answer> {
+            Ok(1.into())
+        }
+
+        fn part2(_: bool) -> anyhow::Result<Answer> {
+            panic!("part 2 exploded");
+        }
+    }
+
+    #[test]
+    fn run_catches_panics() {
+        let result = Panicky.run("good".to_string(), &[1, 2]);
+        assert_eq!(result.parts[0].answer, Ok(Answer::Int(1)));
+        assert_eq!(
+            result.parts[1].answer,
+            Err(Failure::Panicked("part 2 exploded".to_string()))
+        );
+
+        let result = Panicky.run("bad".to_string(), &[1, 2]);
+        for part in result.parts {
+            assert_eq!(
+                part.answer,
+                Err(Failure::Parse(Box::new(Failure::Panicked(
+                    "bad input".to_string()
+                ))))
+            );
+        }
+    }
+
+    #[test]
+    fn catch_panic_catches_rayon_workers() {
+        use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+        let result = catch_panic(|| {
+            (0..64).into_par_iter().for_each(|i| {
+                if i == 63 {
+                    panic!("worker exploded");
+                }
+            })
+        });
+        assert_eq!(result, Err("worker exploded".to_string()));
+    }
+
+    #[test]
+    fn run_reports_errors() {
+        let result = Panicky.run("invalid".to_string(), &[1]);
+        assert_eq!(
+            result.parts[0].answer,
+            Err(Failure::Parse(Box::new(Failure::Error(
+                "line 1: invalid input".to_string()
+            ))))
+        );
+        assert_eq!(
+            result.parts[0].answer.as_ref().unwrap_err().to_string(),
+            "parse error: line 1: invalid input"
+        );
+    }
+
+    #[derive(Clone)]
+    struct Slow;
+
+    impl Solution for Slow {
+        const DAY: u8 = 1;
+        const TITLE: &'static str = "Slow";
+
+        type Model = ();
+
+        fn parse(_: String) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn part1(_: ()) -> anyhow::Result<Answer> {
+            Ok(1.into())
+        }
+
+        fn part2(_: ()) -> anyhow::Result<Answer> {
+            thread::sleep(Duration::from_secs(5));
+            Ok(2.into())
+        }
+    }
+
+    #[test]
+    fn run_with_timeout_abandons_slow_parts() {
+        let timeout = Duration::from_millis(50);
+        let result = Slow.run_with_timeout(String::new(), &[1, 2], Some(timeout));
+        assert_eq!(result.parts[0].answer, Ok(Answer::Int(1)));
+        assert_eq!(result.parts[1].answer, Err(Failure::TimedOut(timeout)));
+    }
+
+    #[test]
+    fn get_day_finds_registered_days() {
+        assert_eq!(crate::get_day(7).map(|d| d.title()), Some("Bridge Repair"));
+        assert!(crate::get_day(26).is_none());
+    }
+}
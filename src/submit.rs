@@ -0,0 +1,409 @@
+//! `aoc2024 submit`, which sends an answer for a day's part to the Advent of Code site.
+//!
+//! Every guess and what the site said about it is kept in `.cache/`, so the same wrong answer
+//! is never sent twice, and neither is one that an earlier "too high" or "too low" already rules
+//! out.  Accepted answers are stored in `answers/dN.json` against `input/dN`, so later runs check
+//! them.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::exit,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, Context, Result};
+use aoc2024::answers::{Answers, ANSWERS_DIR};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    args::SubmitArgs,
+    output::fmt_duration,
+    site::{self, strip_tags, unescape, write, Http, Site, Ureq, CACHE_DIR},
+};
+
+/// What the site said about a guess it checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// wrong, without saying which way
+    Wrong,
+}
+
+impl Verdict {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too high",
+            Verdict::TooLow => "too low",
+            Verdict::Wrong => "wrong",
+        }
+    }
+}
+
+/// The site's response to a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Response {
+    Checked(Verdict),
+    /// an answer was sent too recently, try again after the wait if the site said how long
+    TooSoon(Option<Duration>),
+    /// the part has been solved already, or part 1 hasn't been
+    WrongLevel,
+    /// anything else, as plain text
+    Unrecognized(String),
+}
+
+/// Work out what the site said from the response page.  The message is the text of the page's
+/// `<article>`, which has been stable across years.
+pub fn parse_response(page: &str) -> Response {
+    let article = match (page.find("<article>"), page.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &page[start..end],
+        _ => page,
+    };
+    let text = unescape(&strip_tags(article));
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if text.contains("That's the right answer") {
+        Response::Checked(Verdict::Correct)
+    } else if text.contains("That's not the right answer") {
+        Response::Checked(if text.contains("too high") {
+            Verdict::TooHigh
+        } else if text.contains("too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        })
+    } else if text.contains("You gave an answer too recently") {
+        Response::TooSoon(parse_wait(&text))
+    } else if text.contains("You don't seem to be solving the right level") {
+        Response::WrongLevel
+    } else {
+        Response::Unrecognized(text)
+    }
+}
+
+/// The wait in a message like "You have 1m 5s left to wait."
+fn parse_wait(text: &str) -> Option<Duration> {
+    let end = text.find(" left to wait")?;
+    let mut secs = None;
+    // the wait is every word like `1m` or `5s` just before "left to wait"
+    for word in text[..end].split_whitespace().rev() {
+        let Some((n, unit)) = word
+            .find(|c: char| !c.is_ascii_digit())
+            .filter(|&split| split > 0)
+            .map(|split| word.split_at(split))
+        else {
+            break;
+        };
+        let Ok(n) = n.parse::<u64>() else {
+            break;
+        };
+        let unit = match unit {
+            "h" => 3600,
+            "m" => 60,
+            "s" => 1,
+            _ => break,
+        };
+        secs = Some(secs.unwrap_or(0) + n * unit);
+    }
+    secs.map(Duration::from_secs)
+}
+
+/// A submitted answer and what the site said about it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Guess {
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+/// Every guess for one day, kept in `.cache/YEAR-dN-guesses.json`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Guesses {
+    #[serde(default)]
+    pub part1: Vec<Guess>,
+    #[serde(default)]
+    pub part2: Vec<Guess>,
+    /// when the site will take another answer, in seconds since the epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait_until: Option<u64>,
+}
+
+impl Guesses {
+    fn path(year: u16, day: u8) -> PathBuf {
+        Path::new(CACHE_DIR).join(format!("{year}-d{day}-guesses.json"))
+    }
+
+    fn load(year: u16, day: u8) -> Result<Self> {
+        Guesses::read(&Guesses::path(year, day))
+    }
+
+    /// Read a guesses file, or no guesses if it doesn't exist yet.
+    fn read(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .with_context(|| format!("invalid guesses file {}", path.display())),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Guesses::default()),
+            Err(e) => Err(e).with_context(|| format!("couldn't read {}", path.display())),
+        }
+    }
+
+    fn save(&self, year: u16, day: u8) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).expect("guesses are always serializable");
+        write(&Guesses::path(year, day), &(json + "\n"))
+    }
+
+    pub fn part(&self, part: u8) -> &[Guess] {
+        if part == 1 {
+            &self.part1
+        } else {
+            &self.part2
+        }
+    }
+
+    fn part_mut(&mut self, part: u8) -> &mut Vec<Guess> {
+        if part == 1 {
+            &mut self.part1
+        } else {
+            &mut self.part2
+        }
+    }
+
+    /// Why an answer shouldn't be sent, going by the earlier guesses, if there's a reason.
+    pub fn rule_out(&self, part: u8, answer: &str) -> Option<String> {
+        let guesses = self.part(part);
+        if let Some(correct) = guesses.iter().find(|g| g.verdict == Verdict::Correct) {
+            return Some(format!(
+                "part {part} was already solved with {}",
+                correct.answer
+            ));
+        }
+        if let Some(same) = guesses.iter().find(|g| g.answer == answer) {
+            return Some(format!(
+                "{answer} was already submitted and was {}",
+                same.verdict.as_str()
+            ));
+        }
+
+        // the site only hints for numbers, and any earlier hint bounds the answer
+        let answer: i128 = answer.parse().ok()?;
+        let bound = |verdict| {
+            guesses
+                .iter()
+                .filter(move |g| g.verdict == verdict)
+                .filter_map(|g| g.answer.parse::<i128>().ok())
+        };
+        if let Some(high) = bound(Verdict::TooHigh).filter(|&n| answer >= n).min() {
+            return Some(format!("{answer} can't be right, {high} was too high"));
+        }
+        if let Some(low) = bound(Verdict::TooLow).filter(|&n| answer <= n).max() {
+            return Some(format!("{answer} can't be right, {low} was too low"));
+        }
+        None
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Solve the part for `input/dN`, for when no answer was given on the command line.
+fn solve(day: u8, part: u8) -> Result<String> {
+    let path = format!("input/d{day}");
+    let input = fs::read_to_string(&path).with_context(|| format!("reading {path}"))?;
+    let solved = aoc2024::solve(day, part, &input)?;
+    if solved.answer == aoc2024::answer::Answer::Incomplete {
+        bail!("day {day} part {part} isn't solved yet");
+    }
+    println!("d{day}p{part} answer: {}", solved.answer);
+    Ok(solved.answer.to_string())
+}
+
+/// Send the answer unless the earlier guesses rule it out, and keep what the site said.
+/// Returns whether the answer was accepted.
+fn submit_answer(site: &mut Site<impl Http>, day: u8, part: u8, answer: &str) -> Result<bool> {
+    let mut guesses = Guesses::load(site.year, day)?;
+    if let Some(wait_until) = guesses.wait_until.filter(|&t| t > now()) {
+        bail!(
+            "the site asked to wait before answering again, {} left",
+            fmt_duration(Duration::from_secs(wait_until - now()))
+        );
+    }
+    if let Some(reason) = guesses.rule_out(part, answer) {
+        bail!("not sending: {reason}");
+    }
+
+    let level = part.to_string();
+    let page = site.post(
+        &format!("day/{day}/answer"),
+        &[("level", &level), ("answer", answer)],
+    )?;
+
+    match parse_response(&page) {
+        Response::Checked(verdict) => {
+            guesses.part_mut(part).push(Guess {
+                answer: answer.to_string(),
+                verdict,
+            });
+            guesses.wait_until = None;
+            guesses.save(site.year, day)?;
+
+            if verdict == Verdict::Correct {
+                let dir = Path::new(ANSWERS_DIR);
+                let mut answers = Answers::load(dir, day)?;
+                answers.set(
+                    Path::new(&format!("input/d{day}")),
+                    part,
+                    answer.to_string(),
+                );
+                answers.save(dir, day)?;
+                println!("{answer} is correct, saved to {ANSWERS_DIR}/d{day}.json");
+                Ok(true)
+            } else {
+                println!("{answer} is {}", verdict.as_str());
+                Ok(false)
+            }
+        }
+        Response::TooSoon(wait) => {
+            if let Some(wait) = wait {
+                guesses.wait_until = Some(now() + wait.as_secs());
+                guesses.save(site.year, day)?;
+                bail!("answered too recently, try again in {}", fmt_duration(wait));
+            }
+            bail!("answered too recently, try again later");
+        }
+        Response::WrongLevel => {
+            bail!("part {part} can't be answered, it's already solved or part 1 isn't")
+        }
+        Response::Unrecognized(text) => bail!("unexpected response: {text}"),
+    }
+}
+
+pub fn submit(args: &SubmitArgs) {
+    let result = (|| {
+        let session = site::session()?;
+        let answer = match &args.answer {
+            Some(answer) => answer.clone(),
+            None => solve(args.day, args.part)?,
+        };
+        let mut site = Site::new(Ureq::new(), &args.base_url, args.year, session);
+        submit_answer(&mut site, args.day, args.part, &answer)
+    })();
+
+    match result {
+        Ok(true) => {}
+        Ok(false) => exit(1),
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(message: &str) -> String {
+        format!("<html><main>\n<article><p>{message}</p></article>\n</main></html>")
+    }
+
+    #[test]
+    fn parses_responses() {
+        assert_eq!(
+            parse_response(&page(
+                "That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer."
+            )),
+            Response::Checked(Verdict::Correct)
+        );
+        assert_eq!(
+            parse_response(&page(
+                "That's not the right answer; your answer is too high.  If you're stuck, ..."
+            )),
+            Response::Checked(Verdict::TooHigh)
+        );
+        assert_eq!(
+            parse_response(&page(
+                "That's not the right answer; your answer is too low."
+            )),
+            Response::Checked(Verdict::TooLow)
+        );
+        assert_eq!(
+            parse_response(&page("That's not the right answer.  If you're stuck, ...")),
+            Response::Checked(Verdict::Wrong)
+        );
+        assert_eq!(
+            parse_response(&page(
+                "You gave an answer too recently; you have to wait after submitting an answer \
+                 before trying again.  You have 1m 5s left to wait."
+            )),
+            Response::TooSoon(Some(Duration::from_secs(65)))
+        );
+        assert_eq!(
+            parse_response(&page(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            )),
+            Response::WrongLevel
+        );
+        assert_eq!(
+            parse_response(&page("Something  <em>else</em>")),
+            Response::Unrecognized("Something else".to_string())
+        );
+    }
+
+    #[test]
+    fn earlier_guesses_rule_out_answers() {
+        let guess = |answer: &str, verdict| Guess {
+            answer: answer.to_string(),
+            verdict,
+        };
+        let guesses = Guesses {
+            part1: vec![
+                guess("500", Verdict::TooHigh),
+                guess("100", Verdict::TooLow),
+                guess("250", Verdict::Wrong),
+            ],
+            part2: vec![guess("abc", Verdict::Correct)],
+            wait_until: None,
+        };
+
+        assert_eq!(guesses.rule_out(1, "300"), None);
+        assert!(guesses
+            .rule_out(1, "250")
+            .unwrap()
+            .contains("already submitted"));
+        assert!(guesses
+            .rule_out(1, "600")
+            .unwrap()
+            .contains("500 was too high"));
+        assert!(guesses
+            .rule_out(1, "100")
+            .unwrap()
+            .contains("already submitted"));
+        assert!(guesses
+            .rule_out(1, "50")
+            .unwrap()
+            .contains("100 was too low"));
+        assert!(guesses
+            .rule_out(2, "xyz")
+            .unwrap()
+            .contains("already solved"));
+    }
+
+    #[test]
+    fn only_a_missing_guesses_file_is_empty() {
+        let dir = std::env::temp_dir().join(format!("aoc2024-guesses-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let guesses = Guesses::read(&dir.join("missing.json")).unwrap();
+        assert!(guesses.part(1).is_empty() && guesses.part(2).is_empty());
+        // a directory can't be read as a file
+        assert!(Guesses::read(&dir).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,790 @@
+//! Terminal visualizations, enabled by the `visualize` feature.
+//!
+//! A day makes its model [`Visualize`] by drawing it into a [`Frame`], and shows it with a
+//! [`Screen`] once per step of its simulation.  The screen only redraws the cells that changed,
+//! waits a tick between frames, and handles the keys listed in its status line: space pauses,
+//! `n` steps one frame while paused, `+` and `-` change the speed, and `q` stops drawing and lets
+//! the part finish at full speed.  The terminal is restored when the screen is dropped, even
+//! when the part panics.
+//!
+//! Screens can also record what they draw to an [asciinema](https://asciinema.org) v2 `.cast`
+//! file, which is a JSON header line and then a JSON line for each frame's output with its time.
+//! Recording works without a terminal too, and then doesn't wait between frames: each frame is
+//! just stamped a tick after the one before.
+//!
+//! Screens only draw once the binary has called [`enable`], which it does when it's built with
+//! the feature and writing to a terminal.  Otherwise, like in tests and benchmarks, a screen does
+//! nothing and costs a branch per frame.
+//!
+//! Ctrl-C can't stop the program while the terminal is in raw mode, so it makes [`Screen::show`]
+//! return [`Interrupted`] instead, from then on.  Days pass it up with `?`, and the binary checks
+//! [`interrupted`] to stop running anything else.
+
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex, MutexGuard, OnceLock, TryLockError,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde_json::json;
+use tracing::warn;
+
+/// How long each frame is shown, unless `--tick-ms` says otherwise.
+pub const DEFAULT_TICK: Duration = Duration::from_millis(20);
+
+/// How long [`Screen::flash`] holds a message.
+const FLASH: Duration = Duration::from_millis(1500);
+
+/// The slowest `-` goes.
+const SLOWEST_TICK: Duration = Duration::from_secs(2);
+
+/// The least the status line is padded to.
+const STATUS_WIDTH: usize = 64;
+
+/// How the binary wants visualizations shown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Settings {
+    /// how long each frame is shown
+    pub tick: Duration,
+    /// whether to draw on the terminal, which needs stdout to be one
+    pub draw: bool,
+    /// the `.cast` file to record to, numbered like `d6-2.cast` for the second screen and on
+    pub record: Option<PathBuf>,
+}
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+/// How many screens have recorded so far, to number their files.
+static RECORDINGS: AtomicUsize = AtomicUsize::new(0);
+
+/// Only one screen can draw at a time, so days run in parallel don't draw over each other.
+static DRAWING: Mutex<()> = Mutex::new(());
+
+/// Set once Ctrl-C is pressed on a screen.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Ctrl-C was pressed while a screen was drawing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interrupted;
+
+impl std::fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "interrupted")
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+/// Whether Ctrl-C has been pressed on a screen, so nothing more should run.
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
+
+/// Let screens draw from now on.  Does nothing without the `visualize` feature, and only the
+/// first call counts.
+pub fn enable(settings: Settings) {
+    if cfg!(feature = "visualize") {
+        let _ = SETTINGS.set(settings);
+    }
+}
+
+/// The colors frames are drawn with, named for what they show so every day looks alike.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Palette {
+    #[default]
+    Text,
+    /// empty space
+    Dim,
+    /// the frame's border
+    Border,
+    /// walls and other obstacles
+    Wall,
+    /// whatever is moving, like a guard
+    Actor,
+    /// something placed or picked out, like a crate
+    Marker,
+    /// messages that need noticing
+    Alert,
+}
+
+impl Palette {
+    /// The color's number in the terminal's 256 color palette.
+    fn ansi(self) -> u8 {
+        match self {
+            Palette::Text | Palette::Border | Palette::Marker => 15,
+            Palette::Dim => 8,
+            Palette::Wall => 11,
+            Palette::Actor => 14,
+            Palette::Alert => 13,
+        }
+    }
+}
+
+/// One character on the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub color: Palette,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Cell {
+            ch: ' ',
+            color: Palette::Text,
+        }
+    }
+}
+
+/// A grid of cells to draw into.  Drawing outside the frame is ignored, so days don't have to
+/// check their bounds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize) -> Self {
+        Frame {
+            width,
+            height,
+            cells: vec![Cell::default(); width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<Cell> {
+        (x < self.width && y < self.height).then(|| self.cells[y * self.width + x])
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, ch: char, color: Palette) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = Cell { ch, color };
+        }
+    }
+
+    /// Write text left to right from a cell, cut off at the frame's edge.
+    pub fn text(&mut self, x: usize, y: usize, text: &str, color: Palette) {
+        for (i, ch) in text.chars().enumerate() {
+            self.set(x + i, y, ch, color);
+        }
+    }
+
+    /// Draw a box around the edge of the frame, leaving the inside from `(1, 1)` for the scene.
+    pub fn border(&mut self) {
+        let (right, bottom) = (self.width.saturating_sub(1), self.height.saturating_sub(1));
+        for x in 1..right {
+            self.set(x, 0, '─', Palette::Border);
+            self.set(x, bottom, '─', Palette::Border);
+        }
+        for y in 1..bottom {
+            self.set(0, y, '│', Palette::Border);
+            self.set(right, y, '│', Palette::Border);
+        }
+        self.set(0, 0, '┌', Palette::Border);
+        self.set(right, 0, '┐', Palette::Border);
+        self.set(0, bottom, '└', Palette::Border);
+        self.set(right, bottom, '┘', Palette::Border);
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.fill(Cell::default());
+    }
+
+    /// The cells that differ from the frame on screen, or every cell when there isn't one.
+    fn changes<'a>(
+        &'a self,
+        shown: Option<&'a Frame>,
+    ) -> impl Iterator<Item = (usize, usize, Cell)> + 'a {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(move |&(i, cell)| shown.is_none_or(|shown| shown.cells[i] != *cell))
+            .map(|(i, &cell)| (i % self.width, i / self.width, cell))
+    }
+}
+
+/// A day's model that can be shown on a [`Screen`].
+pub trait Visualize {
+    /// The width and height of the frame to draw into, which stays the same while a screen
+    /// shows it.
+    fn size(&self) -> (usize, usize);
+
+    /// Draw the model into a cleared frame.
+    fn draw(&self, frame: &mut Frame);
+}
+
+/// The escape codes that draw the cells, leaving out any past `columns` or `rows`.  The cursor
+/// is only moved when the next cell isn't right after the last one.
+fn render(cells: &[(usize, usize, Cell)], (columns, rows): (usize, usize)) -> String {
+    let mut out = String::new();
+    let mut cursor = None;
+    let mut color = None;
+    for &(x, y, cell) in cells {
+        if x >= columns || y >= rows {
+            continue;
+        }
+        if cursor != Some((x, y)) {
+            write!(out, "\x1b[{};{}H", y + 1, x + 1).unwrap();
+        }
+        if color != Some(cell.color) {
+            write!(out, "\x1b[38;5;{}m", cell.color.ansi()).unwrap();
+            color = Some(cell.color);
+        }
+        out.push(cell.ch);
+        cursor = Some((x + 1, y));
+    }
+    out
+}
+
+/// The path for the `n`th recording this run, counting from 0: the path itself for the first,
+/// then `d6-2.cast`, `d6-3.cast`, and so on.
+fn numbered(path: &Path, n: usize) -> PathBuf {
+    if n == 0 {
+        return path.to_path_buf();
+    }
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(ext) => format!("{stem}-{}.{}", n + 1, ext.to_string_lossy()),
+        None => format!("{stem}-{}", n + 1),
+    };
+    path.with_file_name(name)
+}
+
+/// An asciinema v2 recording being written.
+struct Recorder {
+    path: PathBuf,
+    out: BufWriter<File>,
+    /// how far into the recording it is
+    time: Duration,
+}
+
+impl Recorder {
+    /// Start a recording of a screen this big, writing the header.
+    fn create(path: PathBuf, (width, height): (usize, usize)) -> io::Result<Self> {
+        let mut out = BufWriter::new(File::create(&path)?);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let header = json!({
+            "version": 2,
+            "width": width,
+            "height": height,
+            "timestamp": timestamp,
+            "env": { "TERM": "xterm-256color" },
+        });
+        writeln!(out, "{header}")?;
+
+        let mut recorder = Recorder {
+            path,
+            out,
+            time: Duration::ZERO,
+        };
+        recorder.output("\x1b[?25l")?;
+        Ok(recorder)
+    }
+
+    /// Add output at the current time.
+    fn output(&mut self, text: &str) -> io::Result<()> {
+        if text.is_empty() {
+            return Ok(());
+        }
+        let event = json!([self.time.as_secs_f64(), "o", text]);
+        writeln!(self.out, "{event}")
+    }
+
+    /// Show the cursor again and write out what's left.
+    fn finish(mut self) -> io::Result<PathBuf> {
+        self.output("\x1b[0m\x1b[?25h")?;
+        self.out.flush()?;
+        Ok(self.path)
+    }
+}
+
+/// What a key asks the screen to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    Pause,
+    Step,
+    Faster,
+    Slower,
+    /// stop drawing and let the part finish
+    Skip,
+    /// stop everything, for Ctrl-C since raw mode swallows the signal
+    Quit,
+}
+
+/// How fast frames go by, changed by the keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Pacing {
+    tick: Duration,
+    paused: bool,
+}
+
+impl Pacing {
+    /// Change the pace for a key.  Returns whether the frame is done waiting, which is only when
+    /// stepping past it.
+    fn apply(&mut self, control: Control) -> bool {
+        match control {
+            Control::Pause => self.paused = !self.paused,
+            Control::Step => return self.paused,
+            Control::Faster => self.tick /= 2,
+            Control::Slower => {
+                self.tick = (self.tick * 2)
+                    .max(Duration::from_millis(1))
+                    .min(SLOWEST_TICK)
+            }
+            Control::Skip | Control::Quit => {}
+        }
+        false
+    }
+
+    fn status(&self) -> String {
+        let state = if self.paused {
+            "paused, n to step".to_string()
+        } else {
+            format!("{}ms a frame", self.tick.as_millis())
+        };
+        format!("{state} · space pause · +/- speed · q skip")
+    }
+}
+
+/// Somewhere to show a day's frames, which does nothing unless visualizations are enabled and
+/// no other screen is drawing.
+pub struct Screen {
+    session: Option<Session>,
+}
+
+struct Session {
+    pacing: Pacing,
+    /// whether the terminal is being drawn on, rather than only recorded to
+    terminal: bool,
+    /// where to record to, until the first frame gives the recording its size
+    record: Option<PathBuf>,
+    recorder: Option<Recorder>,
+    frame: Frame,
+    /// what's been drawn, None before the first frame
+    shown: Option<Frame>,
+    message: Option<(String, Palette)>,
+    _drawing: MutexGuard<'static, ()>,
+}
+
+impl Screen {
+    pub fn start() -> Self {
+        let settings = SETTINGS.get().filter(|_| !interrupted());
+        let session = settings.and_then(|settings| {
+            let drawing = match DRAWING.try_lock() {
+                Ok(drawing) => drawing,
+                // another day is drawing
+                Err(TryLockError::WouldBlock) => return None,
+                // a part panicked while drawing, and its screen restored the terminal as it
+                // unwound, so there's nothing left half done
+                Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+            };
+            let terminal = settings.draw && terminal::enter().is_ok();
+            if !terminal && settings.record.is_none() {
+                return None;
+            }
+            let record = settings
+                .record
+                .as_deref()
+                .map(|path| numbered(path, RECORDINGS.fetch_add(1, Ordering::Relaxed)));
+            Some(Session {
+                pacing: Pacing {
+                    tick: settings.tick,
+                    paused: false,
+                },
+                terminal,
+                record,
+                recorder: None,
+                frame: Frame::new(0, 0),
+                shown: None,
+                message: None,
+                _drawing: drawing,
+            })
+        });
+        Screen { session }
+    }
+
+    /// Draw the model and wait a tick, or until it's stepped past while paused.
+    pub fn show(&mut self, scene: &impl Visualize) -> Result<(), Interrupted> {
+        if let Some(session) = &mut self.session {
+            session.draw(scene);
+            let tick = session.pacing.tick;
+            self.wait(tick);
+        }
+        Screen::check()
+    }
+
+    /// Show a message under the frame and hold it there for a moment.
+    pub fn flash(&mut self, message: &str, color: Palette) -> Result<(), Interrupted> {
+        if let Some(session) = &mut self.session {
+            session.message = Some((message.to_string(), color));
+            session.draw_status();
+            self.wait(FLASH);
+            if let Some(session) = &mut self.session {
+                session.message = None;
+                session.draw_status();
+            }
+        }
+        Screen::check()
+    }
+
+    fn check() -> Result<(), Interrupted> {
+        if interrupted() {
+            Err(Interrupted)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Wait until the time is up, and move the recording on by however long that was.
+    fn wait(&mut self, duration: Duration) {
+        let started = Instant::now();
+        let terminal = self.session.as_ref().is_some_and(|s| s.terminal);
+        if terminal {
+            self.wait_for_keys(duration);
+        }
+        if let Some(recorder) = self.session.as_mut().and_then(|s| s.recorder.as_mut()) {
+            // without a terminal there's nobody watching, so there's no need to really wait
+            recorder.time += if terminal {
+                started.elapsed()
+            } else {
+                duration
+            };
+        }
+    }
+
+    /// Wait, handling keys, until the time is up and the screen isn't paused.
+    fn wait_for_keys(&mut self, duration: Duration) {
+        let deadline = Instant::now() + duration;
+        while let Some(session) = &mut self.session {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() && !session.pacing.paused {
+                return;
+            }
+            let poll = if session.pacing.paused {
+                SLOWEST_TICK
+            } else {
+                left
+            };
+
+            match terminal::control(poll) {
+                None => {}
+                Some(Control::Skip) => self.stop(),
+                Some(Control::Quit) => {
+                    INTERRUPTED.store(true, Ordering::Relaxed);
+                    self.stop();
+                }
+                Some(control) => {
+                    let stepped = session.pacing.apply(control);
+                    session.draw_status();
+                    if stepped {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    fn stop(&mut self) {
+        let Some(session) = self.session.take() else {
+            return;
+        };
+        if session.terminal {
+            terminal::leave();
+        }
+        match session.recorder.map(Recorder::finish) {
+            Some(Ok(path)) => eprintln!("recording saved: {}", path.display()),
+            Some(Err(e)) => warn!("couldn't finish recording: {e}"),
+            None => {}
+        }
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl Session {
+    fn draw(&mut self, scene: &impl Visualize) {
+        let (width, height) = scene.size();
+        if (self.frame.width, self.frame.height) != (width, height) {
+            self.frame = Frame::new(width, height);
+            self.shown = None;
+        }
+        self.frame.clear();
+        scene.draw(&mut self.frame);
+
+        if self.shown.is_none() {
+            // the status line goes under the frame
+            let size = (width.max(STATUS_WIDTH), height + 1);
+            if let Some(path) = self.record.take() {
+                match Recorder::create(path.clone(), size) {
+                    Ok(recorder) => self.recorder = Some(recorder),
+                    Err(e) => warn!("couldn't record to {}: {e}", path.display()),
+                }
+            }
+        }
+
+        let changes: Vec<_> = self.frame.changes(self.shown.as_ref()).collect();
+        self.output(&changes);
+        if self.shown.is_none() {
+            self.draw_status();
+        }
+        match &mut self.shown {
+            Some(shown) => shown.clone_from(&self.frame),
+            None => self.shown = Some(self.frame.clone()),
+        }
+    }
+
+    /// The line under the frame: the message if there is one, otherwise the keys, which are
+    /// left out of recordings made without a terminal since nobody can press them.
+    fn draw_status(&mut self) {
+        let (text, color) = match &self.message {
+            Some((message, color)) => (message.clone(), *color),
+            None if self.terminal => (self.pacing.status(), Palette::Dim),
+            None => (String::new(), Palette::Dim),
+        };
+        // padded to cover whatever longer status was there before
+        let width = self.frame.width.max(text.chars().count()).max(STATUS_WIDTH);
+        let mut line = Frame::new(width, 1);
+        line.text(0, 0, &text, color);
+        let y = self.frame.height;
+        let cells: Vec<_> = line
+            .changes(None)
+            .map(|(x, _, cell)| (x, y, cell))
+            .collect();
+        self.output(&cells);
+    }
+
+    /// Draw cells on the terminal and add them to the recording.
+    fn output(&mut self, cells: &[(usize, usize, Cell)]) {
+        if self.terminal {
+            // some terminals don't know their size, so draw everything for those
+            let size = terminal::size()
+                .filter(|&(columns, rows)| columns > 0 && rows > 0)
+                .unwrap_or((usize::MAX, usize::MAX));
+            terminal::write(&render(cells, size));
+        }
+        if let Some(recorder) = &mut self.recorder {
+            if let Err(e) = recorder.output(&render(cells, (usize::MAX, usize::MAX))) {
+                warn!("couldn't record to {}: {e}", recorder.path.display());
+                self.recorder = None;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "visualize")]
+mod terminal {
+    use std::io::{self, Write};
+
+    use crossterm::{
+        cursor::{Hide, Show},
+        event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+        execute,
+        style::ResetColor,
+        terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+    };
+
+    use super::Control;
+
+    pub(super) fn enter() -> io::Result<()> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)
+    }
+
+    pub(super) fn leave() {
+        // there's nothing more to be done if the terminal can't be restored
+        let _ = execute!(io::stdout(), ResetColor, Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+
+    pub(super) fn size() -> Option<(usize, usize)> {
+        let (columns, rows) = terminal::size().ok()?;
+        Some((columns.into(), rows.into()))
+    }
+
+    pub(super) fn write(text: &str) {
+        let mut stdout = io::stdout().lock();
+        let _ = stdout.write_all(text.as_bytes());
+        let _ = stdout.flush();
+    }
+
+    /// Wait up to `timeout` for a key, and what it asks for.
+    pub(super) fn control(timeout: std::time::Duration) -> Option<Control> {
+        if !event::poll(timeout).ok()? {
+            return None;
+        }
+        let Event::Key(key) = event::read().ok()? else {
+            return None;
+        };
+        if key.kind == KeyEventKind::Release {
+            return None;
+        }
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                Some(Control::Quit)
+            }
+            KeyCode::Char(' ') => Some(Control::Pause),
+            KeyCode::Char('n') | KeyCode::Right => Some(Control::Step),
+            KeyCode::Char('+' | '=') | KeyCode::Up => Some(Control::Faster),
+            KeyCode::Char('-') | KeyCode::Down => Some(Control::Slower),
+            KeyCode::Char('q') | KeyCode::Esc => Some(Control::Skip),
+            _ => None,
+        }
+    }
+}
+
+/// Without the feature the terminal is never drawn on, so none of these are reached.
+#[cfg(not(feature = "visualize"))]
+mod terminal {
+    use std::io;
+
+    use super::Control;
+
+    pub(super) fn enter() -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    pub(super) fn leave() {}
+
+    pub(super) fn size() -> Option<(usize, usize)> {
+        None
+    }
+
+    pub(super) fn write(_text: &str) {}
+
+    pub(super) fn control(_timeout: std::time::Duration) -> Option<Control> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_draws_inside_its_bounds() {
+        let mut frame = Frame::new(4, 3);
+        frame.border();
+        frame.text(1, 1, "abc", Palette::Actor);
+
+        let row = |y| -> String { (0..4).map(|x| frame.get(x, y).unwrap().ch).collect() };
+        assert_eq!(row(0), "┌──┐");
+        // text runs over the border, but not past the edge
+        assert_eq!(row(1), "│abc");
+        assert_eq!(frame.get(1, 1).unwrap().color, Palette::Actor);
+        assert_eq!(row(2), "└──┘");
+        assert_eq!(frame.get(4, 1), None);
+    }
+
+    #[test]
+    fn only_changed_cells_are_redrawn() {
+        let mut frame = Frame::new(3, 2);
+        assert_eq!(frame.changes(None).count(), 6);
+
+        let shown = frame.clone();
+        frame.set(2, 1, '^', Palette::Actor);
+        let changes: Vec<_> = frame.changes(Some(&shown)).collect();
+        assert_eq!(
+            changes,
+            vec![(
+                2,
+                1,
+                Cell {
+                    ch: '^',
+                    color: Palette::Actor
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn render_moves_only_between_runs_of_cells() {
+        let cell = |ch, color| Cell { ch, color };
+        let cells = [
+            (0, 0, cell('#', Palette::Wall)),
+            (1, 0, cell('.', Palette::Dim)),
+            (2, 0, cell('.', Palette::Dim)),
+            (1, 2, cell('^', Palette::Actor)),
+            (9, 0, cell('x', Palette::Text)),
+        ];
+        assert_eq!(
+            render(&cells, (5, 5)),
+            "\x1b[1;1H\x1b[38;5;11m#\x1b[38;5;8m..\x1b[3;2H\x1b[38;5;14m^"
+        );
+    }
+
+    #[test]
+    fn recordings_are_numbered() {
+        let path = Path::new("casts/d6.cast");
+        assert_eq!(numbered(path, 0), Path::new("casts/d6.cast"));
+        assert_eq!(numbered(path, 1), Path::new("casts/d6-2.cast"));
+        assert_eq!(numbered(Path::new("d6"), 2), Path::new("d6-3"));
+    }
+
+    #[test]
+    fn recorder_writes_a_cast() {
+        let path = std::env::temp_dir().join(format!("aoc2024-{}-test.cast", std::process::id()));
+        let mut recorder = Recorder::create(path.clone(), (12, 4)).unwrap();
+        recorder.time += Duration::from_millis(20);
+        recorder.output("\x1b[1;1H#").unwrap();
+        recorder.finish().unwrap();
+
+        let cast = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines: Vec<serde_json::Value> = cast
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(
+            (lines[0]["width"].as_u64(), lines[0]["height"].as_u64()),
+            (Some(12), Some(4))
+        );
+        assert_eq!(lines[2], json!([0.02, "o", "\x1b[1;1H#"]));
+        assert_eq!(lines.len(), 4);
+    }
+
+    #[test]
+    fn pacing_keys() {
+        let mut pacing = Pacing {
+            tick: Duration::from_millis(20),
+            paused: false,
+        };
+        assert!(!pacing.apply(Control::Step));
+        pacing.apply(Control::Faster);
+        assert_eq!(pacing.tick, Duration::from_millis(10));
+        pacing.apply(Control::Slower);
+        pacing.apply(Control::Slower);
+        assert_eq!(pacing.tick, Duration::from_millis(40));
+
+        pacing.apply(Control::Pause);
+        assert!(pacing.status().starts_with("paused"));
+        assert!(pacing.apply(Control::Step));
+
+        // a tick of zero can still be slowed down
+        pacing.tick = Duration::ZERO;
+        pacing.apply(Control::Slower);
+        assert_eq!(pacing.tick, Duration::from_millis(1));
+    }
+}
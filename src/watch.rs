@@ -0,0 +1,326 @@
+//! `--watch`, which re-runs the selected days whenever their source, examples, or input change.
+//!
+//! The running binary can't pick up changes to a day's source, so every run goes through
+//! `cargo run` with the same arguments and `-f json`, which rebuilds first when needed.  The
+//! records it prints are compared against the previous run's to show what changed.
+
+use std::{
+    collections::HashMap,
+    env,
+    ffi::OsString,
+    io::{self, Write},
+    path::Path,
+    process::{exit, Command, Stdio},
+    sync::mpsc,
+    time::Duration,
+};
+
+use notify::{RecursiveMode, Watcher};
+use serde::Deserialize;
+use termion::{clear, color, cursor, style};
+
+use crate::{
+    args::Args,
+    example_order,
+    output::{fmt_duration, Status},
+    STDIN,
+};
+
+/// Editors often write a file in several steps, so wait this long for things to settle.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// The parts of a JSON record that are compared between runs.
+#[derive(Debug, Clone, Deserialize)]
+struct Seen {
+    day: u8,
+    part: u8,
+    input: String,
+    answer: Option<serde_json::Value>,
+    error: Option<String>,
+    status: Status,
+    solve_ns: u64,
+}
+
+impl Seen {
+    fn key(&self) -> (u8, u8, String) {
+        (self.day, self.part, self.input.clone())
+    }
+
+    fn name(&self) -> String {
+        if self.input == format!("input/d{}", self.day) {
+            format!("d{}p{}", self.day, self.part)
+        } else {
+            format!("d{}p{} [{}]", self.day, self.part, self.input)
+        }
+    }
+
+    /// The answer as it's printed, or the error or status when there isn't one.
+    fn outcome(&self) -> String {
+        match (&self.answer, &self.error) {
+            (_, Some(error)) => error.clone(),
+            (Some(serde_json::Value::String(s)), None) => s.clone(),
+            (Some(serde_json::Value::Null) | None, None) => self.status.as_str().to_string(),
+            (Some(answer), None) => answer.to_string(),
+        }
+    }
+}
+
+pub fn watch(args: &Args) {
+    if args.bench.is_some() {
+        eprintln!("Error: --watch and --bench can't be used together");
+        exit(1);
+    }
+    if args.input.iter().any(|i| i == STDIN) {
+        eprintln!("Error: --watch can't re-read stdin");
+        exit(1);
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).unwrap_or_else(|e| {
+        eprintln!("Error: couldn't watch for changes: {e}");
+        exit(1);
+    });
+    // the directories are watched rather than the files, so files that are created later or
+    // replaced by an editor are still noticed
+    for dir in ["src", "examples", "input"] {
+        if Path::new(dir).is_dir() {
+            if let Err(e) = watcher.watch(Path::new(dir), RecursiveMode::NonRecursive) {
+                eprintln!("Error: couldn't watch {dir}: {e}");
+                exit(1);
+            }
+        }
+    }
+    for input in &args.input {
+        // inputs in the watched directories are already covered
+        let _ = watcher.watch(Path::new(input), RecursiveMode::NonRecursive);
+    }
+
+    let mut previous = HashMap::new();
+    loop {
+        previous = run_once(&previous);
+        println!(
+            "{grey}   watching for changes to days {days:?}, ctrl-c to stop{reset}",
+            grey = color::Fg(color::LightBlack),
+            reset = style::Reset,
+            days = args.days.0,
+        );
+        let _ = io::stdout().flush();
+
+        // wait for a change to one of the selected days' files, then for the writes to settle
+        loop {
+            let Ok(event) = rx.recv() else {
+                return;
+            };
+            if event.is_ok_and(|e| e.paths.iter().any(|p| is_watched(args, p))) {
+                break;
+            }
+        }
+        while rx.recv_timeout(DEBOUNCE).is_ok() {}
+    }
+}
+
+/// Whether a changed file belongs to one of the selected days.
+fn is_watched(args: &Args, path: &Path) -> bool {
+    if args
+        .input
+        .iter()
+        .any(|input| same_file(Path::new(input), path))
+    {
+        return true;
+    }
+
+    let (Some(dir), Some(name)) = (
+        path.parent().and_then(Path::file_name),
+        path.file_name().and_then(|n| n.to_str()),
+    ) else {
+        return false;
+    };
+    args.days.0.iter().any(|&day| match dir.to_str() {
+        Some("src") => name == format!("d{day}.rs"),
+        Some("examples") => example_order(day, name).is_some(),
+        Some("input") => name == format!("d{day}"),
+        _ => false,
+    })
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Rebuild and run with the same arguments, then print every record next to the previous run's.
+/// Returns this run's records, or the previous ones again if the build or run failed.
+fn run_once(previous: &HashMap<(u8, u8, String), Seen>) -> HashMap<(u8, u8, String), Seen> {
+    print!("{}{}", clear::All, cursor::Goto(1, 1));
+    let _ = io::stdout().flush();
+
+    let output = Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+        .args(cargo_args())
+        .stdin(Stdio::null())
+        .stderr(Stdio::inherit())
+        .output();
+    let output = match output {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("Error: couldn't run cargo: {e}");
+            return previous.clone();
+        }
+    };
+
+    let seen: Vec<Seen> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    if seen.is_empty() {
+        // cargo has already printed why, whether it's a compile error or a missing input
+        return previous.clone();
+    }
+
+    for record in &seen {
+        print_change(record, previous.get(&record.key()));
+    }
+    seen.into_iter().map(|s| (s.key(), s)).collect()
+}
+
+/// `cargo run` with this build's profile and features, and this run's arguments with `-f json`
+/// in place of `--watch` and any format of their own.
+fn cargo_args() -> Vec<OsString> {
+    let mut cargo: Vec<OsString> = vec!["run".into(), "-q".into()];
+    if !cfg!(debug_assertions) {
+        cargo.push("-r".into());
+    }
+    let mut features = vec![];
+    if cfg!(feature = "visualize") {
+        features.push("visualize");
+    }
+    if cfg!(feature = "count-allocs") {
+        features.push("count-allocs");
+    }
+    if !features.is_empty() {
+        cargo.push("-F".into());
+        cargo.push(features.join(",").into());
+    }
+
+    cargo.push("--".into());
+    cargo.extend(forwarded_args(env::args_os().skip(1)));
+    cargo
+}
+
+/// The arguments for each run: `-f json`, then the given ones minus `--watch` and `-f`/`--format`
+/// with its value, since only one format is accepted.
+fn forwarded_args(args: impl IntoIterator<Item = OsString>) -> Vec<OsString> {
+    let mut forwarded: Vec<OsString> = vec!["-f".into(), "json".into()];
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.to_str() {
+            Some("-w" | "--watch") => {}
+            Some("-f" | "--format") => {
+                args.next();
+            }
+            // pico-args also takes the value stuck to the short flag, like `-ftsv`
+            Some(flag) if flag.starts_with("-f") => {}
+            _ => forwarded.push(arg),
+        }
+    }
+    forwarded
+}
+
+/// Print a record, with what changed since the previous run: the answer, the status, and how
+/// much faster or slower it was.
+fn print_change(record: &Seen, before: Option<&Seen>) {
+    let outcome = record.outcome();
+    let status_color = match record.status {
+        Status::Correct => color::Fg(color::Green).to_string(),
+        Status::Wrong | Status::Error | Status::Panicked => color::Fg(color::Red).to_string(),
+        Status::TimedOut => color::Fg(color::Yellow).to_string(),
+        _ => color::Fg(color::LightBlack).to_string(),
+    };
+    let time = Duration::from_nanos(record.solve_ns);
+
+    let mut changes = vec![];
+    if let Some(before) = before {
+        if before.outcome() != outcome {
+            changes.push(format!(
+                "{}was {}",
+                color::Fg(color::Yellow),
+                before.outcome()
+            ));
+        }
+        if before.status != record.status {
+            changes.push(format!("was {}", before.status.as_str()));
+        }
+        if before.solve_ns > 0 && record.solve_ns > 0 {
+            changes.push(fmt_speedup(before.solve_ns, record.solve_ns));
+        }
+    } else {
+        changes.push("new".to_string());
+    }
+
+    println!(
+        "{green}🎄{reset} {blue}{name}{reset} {outcome} {status_color}{status}{reset} {grey}({time}) {changes}{reset}",
+        blue = color::Fg(color::Blue),
+        green = color::Fg(color::Green),
+        grey = color::Fg(color::LightBlack),
+        reset = style::Reset,
+        name = record.name(),
+        status = record.status.as_str(),
+        time = fmt_duration(time),
+        changes = changes.join(&format!("{} · ", color::Fg(color::LightBlack))),
+    );
+}
+
+/// How the new time compares to the old, like `12% faster`.
+fn fmt_speedup(before_ns: u64, after_ns: u64) -> String {
+    let change = (after_ns as f64 - before_ns as f64) / before_ns as f64 * 100.0;
+    // timings wobble by a few percent from run to run
+    if change.abs() < 5.0 {
+        "same speed".to_string()
+    } else if change < 0.0 {
+        format!("{}{:.0}% faster", color::Fg(color::Green), -change)
+    } else {
+        format!("{}{:.0}% slower", color::Fg(color::Red), change)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outcome_of_records() {
+        let seen = |line: &str| serde_json::from_str::<Seen>(line).unwrap();
+        let base = r#""day":7,"part":1,"input":"input/d7","parse_ns":1,"solve_ns":2"#;
+
+        let solved = seen(&format!(r#"{{{base},"answer":3749,"status":"correct"}}"#));
+        assert_eq!(solved.outcome(), "3749");
+        assert_eq!(solved.name(), "d7p1");
+
+        let text = seen(&format!(r#"{{{base},"answer":"abc","status":"unknown"}}"#));
+        assert_eq!(text.outcome(), "abc");
+
+        let incomplete = seen(&format!(
+            r#"{{{base},"answer":null,"status":"incomplete"}}"#
+        ));
+        assert_eq!(incomplete.outcome(), "incomplete");
+
+        let failed = seen(&format!(
+            r#"{{{base},"answer":null,"error":"error: bad","status":"error"}}"#
+        ));
+        assert_eq!(failed.outcome(), "error: bad");
+    }
+
+    #[test]
+    fn forwarded_args_parse() {
+        let forward = |args: &[&str]| forwarded_args(args.iter().map(OsString::from));
+
+        let args = forward(&["-d", "7", "-w", "-f", "tsv", "-e"]);
+        assert_eq!(args, ["-f", "json", "-d", "7", "-e"]);
+        assert!(crate::args::parse_command(args).is_ok());
+
+        let args = forward(&["--watch", "-d", "7", "--format", "pretty", "-ftsv"]);
+        assert_eq!(args, ["-f", "json", "-d", "7"]);
+        assert!(crate::args::parse_command(args).is_ok());
+    }
+}
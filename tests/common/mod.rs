@@ -0,0 +1,87 @@
+//! A stub of the Advent of Code site for the `fetch` and `submit` tests, and a scratch directory
+//! to run the binary in.
+
+use std::{
+    env, fs,
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    path::PathBuf,
+    process,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+/// A request the stub received.
+#[derive(Debug, Clone)]
+pub struct Seen {
+    pub method: String,
+    pub path: String,
+    pub user_agent: String,
+    pub cookie: String,
+    pub body: String,
+}
+
+/// Serve on a free port, answering each request with the handler's status line and body.
+/// Returns the base URL and every request seen so far.
+pub fn stub(
+    handler: impl Fn(&Seen) -> (&'static str, String) + Send + 'static,
+) -> (String, Arc<Mutex<Vec<Seen>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let seen = Arc::new(Mutex::new(vec![]));
+
+    let log = seen.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(&stream);
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let mut request_line = line.split_whitespace();
+            let mut request = Seen {
+                method: request_line.next().unwrap().to_string(),
+                path: request_line.next().unwrap().to_string(),
+                user_agent: String::new(),
+                cookie: String::new(),
+                body: String::new(),
+            };
+
+            let mut length = 0;
+            loop {
+                line.clear();
+                reader.read_line(&mut line).unwrap();
+                let Some((name, value)) = line.trim_end().split_once(": ") else {
+                    break;
+                };
+                match name.to_ascii_lowercase().as_str() {
+                    "user-agent" => request.user_agent = value.to_string(),
+                    "cookie" => request.cookie = value.to_string(),
+                    "content-length" => length = value.parse().unwrap(),
+                    _ => {}
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            request.body = String::from_utf8(body).unwrap();
+
+            let (status, body) = handler(&request);
+            log.lock().unwrap().push(request);
+            write!(
+                stream,
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        }
+    });
+
+    (url, seen)
+}
+
+/// An empty directory to run in, since files are saved relative to the working directory.
+pub fn scratch(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("aoc2024-{}-{name}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
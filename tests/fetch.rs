@@ -0,0 +1,116 @@
+//! Runs `aoc2024 fetch` against a stub of the Advent of Code site on localhost.
+
+mod common;
+
+use std::{
+    fs,
+    path::Path,
+    process::{Command, Output},
+};
+
+use common::{scratch, stub, Seen};
+
+const PAGE: &str = "<article><p>For example:</p>\n\
+    <pre><code>3   4\n4   3\n<em>2</em>   5\n</code></pre></article>";
+
+/// Serve `/2024/day/1/input` and `/2024/day/1`, 404 for anything else.
+fn site(request: &Seen) -> (&'static str, String) {
+    match request.path.as_str() {
+        "/2024/day/1/input" => ("200 OK", "3   4\n4   3\n".to_string()),
+        "/2024/day/1" => ("200 OK", PAGE.to_string()),
+        _ => ("404 Not Found", "not found".to_string()),
+    }
+}
+
+fn fetch(dir: &Path, url: &str, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aoc2024"))
+        .arg("fetch")
+        .args(args)
+        .args(["--year", "2024", "--base-url", url])
+        .env("AOC_SESSION", "abc123")
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn downloads_input_and_example_once() {
+    let (url, seen) = stub(site);
+    let dir = scratch("fetch-once");
+
+    let output = fetch(&dir, &url, &["1", "--example"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        fs::read_to_string(dir.join("input/d1")).unwrap(),
+        "3   4\n4   3\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("examples/d1")).unwrap(),
+        "3   4\n4   3\n2   5\n"
+    );
+
+    {
+        let seen = seen.lock().unwrap();
+        let paths: Vec<&str> = seen.iter().map(|s| s.path.as_str()).collect();
+        assert_eq!(paths, ["/2024/day/1/input", "/2024/day/1"]);
+        for request in seen.iter() {
+            assert_eq!(request.method, "GET");
+            assert!(request.user_agent.starts_with("aoc2024/"), "{request:?}");
+            assert_eq!(request.cookie, "session=abc123");
+        }
+    }
+
+    // everything is on disk now, so there's nothing to ask for
+    let output = fetch(&dir, &url, &["1", "--example"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(seen.lock().unwrap().len(), 2);
+
+    // unless forced, which downloads both again
+    fs::write(dir.join("examples/d1"), "").unwrap();
+    let output = fetch(&dir, &url, &["1", "--example", "--force"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(seen.lock().unwrap().len(), 4);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn reports_missing_days() {
+    let (url, seen) = stub(site);
+    let dir = scratch("fetch-missing");
+
+    let output = fetch(&dir, &url, &["2", "--example"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("has the puzzle been unlocked yet?"),
+        "{stderr}"
+    );
+    assert!(!dir.join("input/d2").exists());
+    // the puzzle page isn't requested after the input wasn't found
+    let paths: Vec<String> = seen
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|s| s.path.clone())
+        .collect();
+    assert_eq!(paths, ["/2024/day/2/input"]);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn needs_a_session() {
+    let dir = scratch("fetch-session");
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc2024"))
+        .args(["fetch", "1"])
+        .env_remove("AOC_SESSION")
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("AOC_SESSION"));
+
+    let _ = fs::remove_dir_all(&dir);
+}
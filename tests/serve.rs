@@ -0,0 +1,105 @@
+//! Runs `aoc2024 serve` on a free port and talks to it over localhost.
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpStream,
+    process::{Child, Command, Stdio},
+};
+
+use serde_json::{json, Value};
+
+/// The server process, killed when the test is done with it.
+struct Server {
+    child: Child,
+    addr: String,
+}
+
+impl Server {
+    fn start() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_aoc2024"))
+            .args(["serve", "--port", "0"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("the server starts");
+
+        let mut line = String::new();
+        BufReader::new(child.stdout.as_mut().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let addr = line
+            .trim()
+            .strip_prefix("listening on http://")
+            .expect("the server prints its address")
+            .to_string();
+
+        Server { child, addr }
+    }
+
+    /// Send a request and return the status code and the JSON body.
+    fn request(&self, method: &str, path: &str, body: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(&self.addr).unwrap();
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\n\r\n{body}",
+            self.addr,
+            body.len()
+        )
+        .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn lists_days() {
+    let server = Server::start();
+    let (status, days) = server.request("GET", "/days", "");
+
+    assert_eq!(status, 200);
+    assert_eq!(days.as_array().unwrap().len(), 25);
+    assert_eq!(days[6], json!({ "day": 7, "title": "Bridge Repair" }));
+}
+
+#[test]
+fn solves_a_posted_input() {
+    let server = Server::start();
+    let example = include_str!("../examples/d7");
+
+    let (status, solved) = server.request("POST", "/days/7/1", example);
+    assert_eq!(status, 200);
+    assert_eq!(solved["answer"], json!(3749));
+    assert!(solved["parse_ns"].is_u64());
+    assert!(solved["solve_ns"].is_u64());
+
+    let (status, solved) = server.request("POST", "/days/7/2", example);
+    assert_eq!(status, 200);
+    assert_eq!(solved["answer"], json!(11387));
+}
+
+#[test]
+fn reports_errors() {
+    let server = Server::start();
+
+    let (status, error) = server.request("POST", "/days/7/1", "190: x");
+    assert_eq!(status, 422);
+    assert!(error["error"]
+        .as_str()
+        .unwrap()
+        .contains("line 1: column 6: can't parse \"x\""));
+
+    assert_eq!(server.request("POST", "/days/26/1", "").0, 404);
+    assert_eq!(server.request("POST", "/days/7/3", "").0, 404);
+    assert_eq!(server.request("GET", "/days/7/1", "").0, 405);
+    assert_eq!(server.request("GET", "/nowhere", "").0, 404);
+}
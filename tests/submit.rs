@@ -0,0 +1,122 @@
+//! Runs `aoc2024 submit` against a stub of the Advent of Code site on localhost.
+
+mod common;
+
+use std::{
+    fs,
+    path::Path,
+    process::{Command, Output},
+};
+
+use common::{scratch, stub, Seen};
+
+fn article(message: &str) -> String {
+    format!("<html><main><article><p>{message}</p></article></main></html>")
+}
+
+/// Judge answers to day 7: 3749 is right for part 1, 11387 for part 2, 7 gets a rate limit,
+/// and anything else is judged against 3749.
+fn site(request: &Seen) -> (&'static str, String) {
+    if request.path != "/2024/day/7/answer" {
+        return ("404 Not Found", "not found".to_string());
+    }
+    let answer = request
+        .body
+        .split('&')
+        .find_map(|field| field.strip_prefix("answer="))
+        .unwrap_or_default();
+
+    let message = match answer.parse::<u64>() {
+        Ok(3749 | 11387) => "That's the right answer!  You are one gold star closer.",
+        Ok(7) => "You gave an answer too recently; you have 30s left to wait.",
+        Ok(n) if n > 3749 => "That's not the right answer; your answer is too high.",
+        Ok(_) => "That's not the right answer; your answer is too low.",
+        Err(_) => "That's not the right answer.",
+    };
+    ("200 OK", article(message))
+}
+
+fn submit(dir: &Path, url: &str, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aoc2024"))
+        .arg("submit")
+        .args(args)
+        .args(["--year", "2024", "--base-url", url])
+        .env("AOC_SESSION", "abc123")
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+#[test]
+fn remembers_wrong_guesses() {
+    let (url, seen) = stub(site);
+    let dir = scratch("submit-guesses");
+
+    let output = submit(&dir, &url, &["7", "1", "5000"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("5000 is too high"));
+    {
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen[0].method, "POST");
+        assert_eq!(seen[0].body, "level=1&answer=5000");
+        assert_eq!(seen[0].cookie, "session=abc123");
+    }
+
+    // neither the same answer nor a higher one is sent again
+    let output = submit(&dir, &url, &["7", "1", "5000"]);
+    assert!(stderr(&output).contains("already submitted and was too high"));
+    let output = submit(&dir, &url, &["7", "1", "6000"]);
+    assert!(stderr(&output).contains("5000 was too high"));
+    assert_eq!(seen.lock().unwrap().len(), 1);
+
+    let output = submit(&dir, &url, &["7", "1", "3749"]);
+    assert!(output.status.success(), "{output:?}");
+    let answers = fs::read_to_string(dir.join("answers/d7.json")).unwrap();
+    assert!(answers.contains("\"input/d7\""), "{answers}");
+    assert!(answers.contains("\"part1\": \"3749\""), "{answers}");
+
+    // once solved, there's nothing more to send for the part
+    let output = submit(&dir, &url, &["7", "1", "3000"]);
+    assert!(stderr(&output).contains("already solved with 3749"));
+    assert_eq!(seen.lock().unwrap().len(), 2);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn waits_when_rate_limited() {
+    let (url, seen) = stub(site);
+    let dir = scratch("submit-wait");
+
+    let output = submit(&dir, &url, &["7", "2", "7"]);
+    assert!(stderr(&output).contains("try again in 30s"), "{output:?}");
+
+    // the wait is remembered, so nothing is sent until it's over
+    let output = submit(&dir, &url, &["7", "2", "11387"]);
+    assert!(stderr(&output).contains("asked to wait"), "{output:?}");
+    assert_eq!(seen.lock().unwrap().len(), 1);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn solves_the_input_when_no_answer_is_given() {
+    let (url, seen) = stub(site);
+    let dir = scratch("submit-solve");
+    fs::create_dir_all(dir.join("input")).unwrap();
+    fs::copy(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/d7"),
+        dir.join("input/d7"),
+    )
+    .unwrap();
+
+    let output = submit(&dir, &url, &["7", "2"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(seen.lock().unwrap()[0].body, "level=2&answer=11387");
+
+    let _ = fs::remove_dir_all(&dir);
+}